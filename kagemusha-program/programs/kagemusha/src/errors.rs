@@ -43,4 +43,127 @@ pub enum KagemushaError {
 
     #[msg("Mint mismatch between accounts.")]
     MintMismatch,
+
+    #[msg("Basket accounts do not match the strategy composition.")]
+    InvalidBasketAccounts,
+
+    #[msg("Vault token account does not belong to this strategy.")]
+    InvalidVaultAccount,
+
+    #[msg("Duplicate mint in basket.")]
+    DuplicateMint,
+
+    #[msg("Amount too small to mint or redeem any shares.")]
+    ZeroShares,
+
+    #[msg("Invalid price feed account.")]
+    InvalidPriceFeed,
+
+    #[msg("Oracle price is stale.")]
+    StalePrice,
+
+    #[msg("Oracle price confidence interval too wide.")]
+    PriceConfidenceTooWide,
+
+    #[msg("Native SOL is not part of the strategy basket.")]
+    NativeMintNotInBasket,
+
+    #[msg("Composition must have between 1 and 10 tokens.")]
+    InvalidTokenCount,
+
+    #[msg("Strategy is private and the depositor has no pass.")]
+    DepositorNotAllowed,
+
+    #[msg("Fee exceeds the protocol maximum.")]
+    FeeTooHigh,
+
+    #[msg("Protocol is paused.")]
+    ProtocolPaused,
+
+    #[msg("Referrer account missing or does not match the position's referrer.")]
+    InvalidReferrer,
+
+    #[msg("Position already has a different referrer.")]
+    ReferrerAlreadySet,
+
+    #[msg("No ownership transfer is pending for this signer.")]
+    NoPendingOwner,
+
+    #[msg("Delegation has expired.")]
+    DelegationExpired,
+
+    #[msg("Rebalance exceeds the delegation's notional limits.")]
+    DelegationLimitExceeded,
+
+    #[msg("Output mint is not allowed by the delegation.")]
+    OutputMintNotAllowed,
+
+    #[msg("Strategy is shut down.")]
+    StrategyShutdown,
+
+    #[msg("Strategy still has shares or unclaimed fees.")]
+    StrategyNotEmpty,
+
+    #[msg("No composition change is pending.")]
+    NoPendingWeights,
+
+    #[msg("Timelock has not elapsed.")]
+    TimelockNotElapsed,
+
+    #[msg("Timelock is shorter than the minimum.")]
+    TimelockTooShort,
+
+    #[msg("Token removed from the composition still holds a balance.")]
+    RemovedTokenNotEmpty,
+
+    #[msg("Rebalance moves away from the target weights and was not announced.")]
+    RebalanceNotAnnounced,
+
+    #[msg("Guardian must be a separate key from the protocol admin.")]
+    InvalidGuardian,
+
+    #[msg("Strategy is frozen by the guardian.")]
+    StrategyFrozen,
+
+    #[msg("Mint is not approved in the protocol's mint registry.")]
+    MintNotApproved,
+
+    #[msg("Governance is not enabled for this strategy.")]
+    GovernanceDisabled,
+
+    #[msg("Governance is already enabled for this strategy.")]
+    GovernanceAlreadyEnabled,
+
+    #[msg("This change needs a passed shareholder proposal.")]
+    GovernanceRequired,
+
+    #[msg("Invalid quorum, threshold or voting period.")]
+    InvalidGovernanceParams,
+
+    #[msg("Proposal is not open for voting.")]
+    VotingClosed,
+
+    #[msg("Proposal voting period has not ended.")]
+    VotingNotEnded,
+
+    #[msg("Proposal has already been executed or cancelled.")]
+    ProposalNotActive,
+
+    #[msg("Proposal did not reach quorum and threshold.")]
+    ProposalNotPassed,
+
+    #[msg("First deposit into a strategy is below the minimum value.")]
+    DepositTooSmall,
+
+    #[msg("Swap moved more than the swap amount in, or returned less than the minimum out.")]
+    SwapResultMismatch,
+
+    #[msg("Vote weight exceeds the shares the voter deposited before the proposal was created.")]
+    VoteWeightNotSnapshotted,
+
+    #[msg("No fee increase is pending.")]
+    NoPendingFees,
+
+    #[msg("New owner must be a non-default key other than the current owner.")]
+    InvalidNewOwner,
 }
@@ -0,0 +1,315 @@
+use anchor_lang::prelude::*;
+use crate::fees::FeeSplit;
+use crate::state::{StrategyFeeParams, StrategyStatus};
+
+/// Curator fee shares and swap fees paid to the strategy owner.
+#[event]
+pub struct CuratorFeesClaimed {
+    pub strategy: Pubkey,
+    pub curator: Pubkey,
+    pub shares: u64,
+    /// Swap fee tokens claimed per basket slot, aligned with the strategy's mints
+    pub amounts: [u64; 10],
+    pub timestamp: i64,
+}
+
+/// Protocol fee shares and swap fees paid to the treasury.
+#[event]
+pub struct ProtocolFeesClaimed {
+    pub strategy: Pubkey,
+    pub treasury: Pubkey,
+    pub shares: u64,
+    /// Swap fee tokens claimed per basket slot, aligned with the strategy's mints
+    pub amounts: [u64; 10],
+    pub timestamp: i64,
+}
+
+/// Referral fee shares minted to a referrer.
+#[event]
+pub struct ReferrerFeesClaimed {
+    pub strategy: Pubkey,
+    pub referrer: Pubkey,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+/// Shares minted for a deposit, with the entry fee split.
+#[event]
+pub struct Deposited {
+    pub strategy: Pubkey,
+    pub user: Pubkey,
+    /// USD value deposited (6 decimals)
+    pub value: u64,
+    /// Shares minted to the depositor
+    pub shares: u64,
+    /// Entry fee shares by recipient
+    pub fees: FeeSplit,
+    pub timestamp: i64,
+}
+
+/// Shares redeemed in a withdrawal, with the exit fee split.
+#[event]
+pub struct Withdrawn {
+    pub strategy: Pubkey,
+    pub user: Pubkey,
+    /// Shares burned from the user
+    pub shares: u64,
+    /// Exit fee shares by recipient
+    pub fees: FeeSplit,
+    /// USD value paid out (6 decimals)
+    pub value: u64,
+    pub timestamp: i64,
+}
+
+/// Strategy owner proposed handing the strategy to `pending_owner`.
+#[event]
+pub struct OwnershipTransferProposed {
+    pub strategy: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Pending owner accepted the strategy.
+#[event]
+pub struct OwnershipTransferred {
+    pub strategy: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Strategy owner withdrew a pending ownership transfer.
+#[event]
+pub struct OwnershipTransferCancelled {
+    pub strategy: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Strategy owner set or cleared the delegated rebalancer.
+#[event]
+pub struct RebalancerChanged {
+    pub strategy: Pubkey,
+    pub previous_rebalancer: Pubkey,
+    pub rebalancer: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Strategy owner created or updated a rebalance delegation.
+#[event]
+pub struct DelegationSet {
+    pub strategy: Pubkey,
+    pub delegate: Pubkey,
+    pub expires_at: i64,
+    pub max_notional_per_rebalance: u64,
+    pub max_daily_notional: u64,
+    pub timestamp: i64,
+}
+
+/// A rebalance delegation was revoked.
+#[event]
+pub struct DelegationRevoked {
+    pub strategy: Pubkey,
+    pub delegate: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Strategy status changed by its owner or the protocol.
+#[event]
+pub struct StrategyStatusChanged {
+    pub strategy: Pubkey,
+    pub previous_status: StrategyStatus,
+    pub status: StrategyStatus,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Strategy retired and its accounts closed.
+#[event]
+pub struct StrategyClosed {
+    pub strategy: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Composition change announced, executable from `eta`.
+#[event]
+pub struct WeightsProposed {
+    pub strategy: Pubkey,
+    pub num_tokens: u8,
+    pub target_weights: [u16; 10],
+    pub mints: [Pubkey; 10],
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+/// Pending composition became the strategy's target.
+#[event]
+pub struct WeightsExecuted {
+    pub strategy: Pubkey,
+    pub num_tokens: u8,
+    pub target_weights: [u16; 10],
+    pub mints: [Pubkey; 10],
+    pub timestamp: i64,
+}
+
+/// Pending composition withdrawn by the owner.
+#[event]
+pub struct WeightsCancelled {
+    pub strategy: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Off-target rebalance announced, executable from `eta`.
+#[event]
+pub struct RebalanceAnnounced {
+    pub strategy: Pubkey,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub max_amount_in: u64,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+/// Announced off-target rebalance withdrawn by the owner.
+#[event]
+pub struct RebalanceAnnouncementCancelled {
+    pub strategy: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Guardian froze a strategy into withdraw-only mode.
+#[event]
+pub struct StrategyFrozen {
+    pub strategy: Pubkey,
+    pub guardian: Pubkey,
+    pub previous_status: StrategyStatus,
+    pub timestamp: i64,
+}
+
+/// Protocol admin lifted a guardian freeze.
+#[event]
+pub struct StrategyUnfrozen {
+    pub strategy: Pubkey,
+    pub admin: Pubkey,
+    pub status: StrategyStatus,
+    pub timestamp: i64,
+}
+
+/// Protocol admin approved or updated a basket mint.
+#[event]
+pub struct MintApproved {
+    pub mint: Pubkey,
+    pub oracle: Pubkey,
+    pub risk_tier: u8,
+    pub timestamp: i64,
+}
+
+/// Protocol admin removed a basket mint's approval.
+#[event]
+pub struct MintRemoved {
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Strategy owner set or cleared the depositor attestor.
+#[event]
+pub struct AttestorChanged {
+    pub strategy: Pubkey,
+    pub previous_attestor: Pubkey,
+    pub attestor: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Owner or attestor issued a DepositorPass.
+#[event]
+pub struct DepositorAdded {
+    pub strategy: Pubkey,
+    pub depositor: Pubkey,
+    pub issued_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Owner or attestor revoked a DepositorPass.
+#[event]
+pub struct DepositorRemoved {
+    pub strategy: Pubkey,
+    pub depositor: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Strategy owner put the strategy under shareholder governance.
+#[event]
+pub struct GovernanceEnabled {
+    pub strategy: Pubkey,
+    pub quorum_bps: u16,
+    pub threshold_bps: u16,
+    pub voting_period: i64,
+    pub timestamp: i64,
+}
+
+/// Strategy owner put a change to a shareholder vote.
+#[event]
+pub struct ProposalCreated {
+    pub strategy: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub voting_ends_at: i64,
+    pub timestamp: i64,
+}
+
+/// Shareholder voted on a proposal.
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub approve: bool,
+    pub timestamp: i64,
+}
+
+/// Passed proposal was applied to its strategy.
+#[event]
+pub struct ProposalExecuted {
+    pub strategy: Pubkey,
+    pub proposal: Pubkey,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub timestamp: i64,
+}
+
+/// Strategy owner withdrew a proposal.
+#[event]
+pub struct ProposalCancelled {
+    pub strategy: Pubkey,
+    pub proposal: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Fee increase announced, executable from `eta`.
+#[event]
+pub struct FeesProposed {
+    pub strategy: Pubkey,
+    pub fees: StrategyFeeParams,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+/// Pending fee increase became the strategy's fee rates.
+#[event]
+pub struct FeesExecuted {
+    pub strategy: Pubkey,
+    pub fees: StrategyFeeParams,
+    pub timestamp: i64,
+}
+
+/// Pending fee increase withdrawn by the owner.
+#[event]
+pub struct FeesCancelled {
+    pub strategy: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use crate::errors::KagemushaError;
+
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// `bps` basis points of `amount`, rounded down.
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(KagemushaError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(fee).map_err(|_| error!(KagemushaError::MathOverflow))
+}
+
+/// Shares owed to the curator for `elapsed` seconds of an annual
+/// management fee of `fee_bps` on `total_shares`. Rounds down.
+pub fn management_fee_shares(total_shares: u64, fee_bps: u16, elapsed: i64) -> Result<u64> {
+    if total_shares == 0 || fee_bps == 0 || elapsed <= 0 {
+        return Ok(0);
+    }
+    let fee = (total_shares as u128)
+        .checked_mul(fee_bps as u128)
+        .ok_or(KagemushaError::MathOverflow)?
+        .checked_mul(elapsed as u128)
+        .ok_or(KagemushaError::MathOverflow)?
+        / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+    u64::try_from(fee).map_err(|_| error!(KagemushaError::MathOverflow))
+}
+
+/// Fixed-point scale of share prices (NAV per share)
+pub const SHARE_PRICE_SCALE: u64 = 1_000_000_000;
+
+/// NAV per share, scaled by `SHARE_PRICE_SCALE`.
+pub fn share_price(nav: u64, total_shares: u64) -> Result<u64> {
+    require!(total_shares > 0, KagemushaError::InsufficientFunds);
+    let price = (nav as u128)
+        .checked_mul(SHARE_PRICE_SCALE as u128)
+        .ok_or(KagemushaError::MathOverflow)?
+        / total_shares as u128;
+    u64::try_from(price).map_err(|_| error!(KagemushaError::MathOverflow))
+}
+
+/// Performance fee owed on gains above the high-water mark.
+///
+/// Returns the fee shares to mint and the new high-water mark. The fee is
+/// `fee_bps` of the value gained above `high_water_mark`, paid in shares so
+/// that after minting them the holders keep exactly the rest of the gain.
+/// A zero high-water mark is initialised to the current share price.
+pub fn performance_fee_shares(
+    nav: u64,
+    total_shares: u64,
+    high_water_mark: u64,
+    fee_bps: u16,
+) -> Result<(u64, u64)> {
+    if total_shares == 0 {
+        return Ok((0, high_water_mark));
+    }
+    let price = share_price(nav, total_shares)?;
+    if high_water_mark == 0 {
+        return Ok((0, price));
+    }
+    if price <= high_water_mark || fee_bps == 0 {
+        return Ok((0, high_water_mark));
+    }
+
+    let gain_value = ((price - high_water_mark) as u128)
+        .checked_mul(total_shares as u128)
+        .ok_or(KagemushaError::MathOverflow)?
+        / SHARE_PRICE_SCALE as u128;
+    let fee_value = gain_value * fee_bps as u128 / BPS_DENOMINATOR as u128;
+    let remaining_value = (nav as u128).saturating_sub(fee_value);
+    if fee_value == 0 || remaining_value == 0 {
+        return Ok((0, high_water_mark));
+    }
+
+    let fee_shares = fee_value
+        .checked_mul(total_shares as u128)
+        .ok_or(KagemushaError::MathOverflow)?
+        / remaining_value;
+    let fee_shares = u64::try_from(fee_shares).map_err(|_| error!(KagemushaError::MathOverflow))?;
+    let new_total = total_shares
+        .checked_add(fee_shares)
+        .ok_or(KagemushaError::MathOverflow)?;
+
+    Ok((fee_shares, share_price(nav, new_total)?))
+}
+
+/// A collected fee divided between its recipients, in the fee's own unit
+/// (shares or tokens).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub protocol: u64,
+    pub curator: u64,
+    pub referrer: u64,
+}
+
+impl FeeSplit {
+    pub fn total(&self) -> u64 {
+        self.protocol + self.curator + self.referrer
+    }
+}
+
+/// Divide `amount` between the protocol, an optional referrer and the
+/// curator.
+///
+/// The protocol takes `protocol_share_bps` of the fee and the referrer takes
+/// `referrer_share_bps` of what is left. Both round down and the curator gets
+/// the remainder, so the parts always add up to exactly `amount`.
+pub fn split_fee(amount: u64, protocol_share_bps: u16, referrer_share_bps: u16) -> Result<FeeSplit> {
+    require!(
+        protocol_share_bps as u64 <= BPS_DENOMINATOR && referrer_share_bps as u64 <= BPS_DENOMINATOR,
+        KagemushaError::FeeTooHigh
+    );
+    let protocol = bps_of(amount, protocol_share_bps)?;
+    let referrer = bps_of(amount - protocol, referrer_share_bps)?;
+    Ok(FeeSplit {
+        protocol,
+        curator: amount - protocol - referrer,
+        referrer,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARES: u64 = 1_000_000_000;
+
+    #[test]
+    fn first_crystallization_sets_high_water_mark() {
+        let (fee, hwm) = performance_fee_shares(1_500_000_000, SHARES, 0, 2000).unwrap();
+        assert_eq!(fee, 0);
+        assert_eq!(hwm, 1_500_000_000);
+    }
+
+    #[test]
+    fn gain_above_high_water_mark_pays_fee_on_gain_only() {
+        // NAV per share 1.00 -> 1.50 with a 20% performance fee
+        let (fee, hwm) =
+            performance_fee_shares(1_500_000_000, SHARES, SHARE_PRICE_SCALE, 2000).unwrap();
+
+        // Fee value is 20% of the 0.50 gain on 1000 shares = 100 USD
+        let fee_value = 1_500_000_000u128 * fee as u128 / (SHARES + fee) as u128;
+        assert_eq!(fee_value, 99_999_999);
+        assert_eq!(hwm, 1_400_000_000);
+    }
+
+    #[test]
+    fn drawdown_pays_no_fee_and_keeps_high_water_mark() {
+        let (fee, hwm) =
+            performance_fee_shares(700_000_000, SHARES, SHARE_PRICE_SCALE, 2000).unwrap();
+        assert_eq!(fee, 0);
+        assert_eq!(hwm, SHARE_PRICE_SCALE);
+    }
+
+    #[test]
+    fn recovery_below_high_water_mark_pays_no_fee() {
+        // Drawdown to 0.70, then recovery to 0.95: still under the 1.00 mark
+        let (_, hwm) = performance_fee_shares(700_000_000, SHARES, SHARE_PRICE_SCALE, 2000).unwrap();
+        let (fee, hwm) = performance_fee_shares(950_000_000, SHARES, hwm, 2000).unwrap();
+        assert_eq!(fee, 0);
+        assert_eq!(hwm, SHARE_PRICE_SCALE);
+    }
+
+    #[test]
+    fn recovery_above_high_water_mark_pays_fee_on_new_gain_only() {
+        // Drawdown to 0.70, then recovery to 1.10: only the 0.10 above 1.00 is charged
+        let (_, hwm) = performance_fee_shares(700_000_000, SHARES, SHARE_PRICE_SCALE, 2000).unwrap();
+        let (fee, hwm) = performance_fee_shares(1_100_000_000, SHARES, hwm, 2000).unwrap();
+
+        let fee_value = 1_100_000_000u128 * fee as u128 / (SHARES + fee) as u128;
+        assert_eq!(fee_value, 19_999_999);
+        assert_eq!(hwm, 1_080_000_000);
+    }
+
+    #[test]
+    fn split_fee_rounds_recipients_down_and_gives_curator_the_rest() {
+        // 30% to the protocol, then 10% of the remaining 70% to the referrer
+        let split = split_fee(1_001, 3000, 1000).unwrap();
+        assert_eq!(split.protocol, 300);
+        assert_eq!(split.referrer, 70);
+        assert_eq!(split.curator, 631);
+        assert_eq!(split.total(), 1_001);
+    }
+
+    #[test]
+    fn split_fee_never_pays_out_more_than_the_fee() {
+        for amount in [0, 1, 2, 3, 7, 9_999, u64::MAX / BPS_DENOMINATOR] {
+            for (protocol, referrer) in [(0, 0), (1, 1), (3333, 3333), (9999, 9999), (10_000, 10_000)] {
+                let split = split_fee(amount, protocol, referrer).unwrap();
+                assert_eq!(split.total(), amount);
+            }
+        }
+    }
+
+    #[test]
+    fn split_fee_rejects_shares_above_100_percent() {
+        assert!(split_fee(100, 10_001, 0).is_err());
+        assert!(split_fee(100, 0, 10_001).is_err());
+    }
+}
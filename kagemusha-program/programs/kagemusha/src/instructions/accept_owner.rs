@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::OwnershipTransferred;
+
+/// Accept a pending ownership transfer. The strategy PDA is seeded by its
+/// creator, so its address does not change.
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
+    #[account(
+        mut,
+        constraint = strategy.pending_owner == new_owner.key() @ KagemushaError::NoPendingOwner
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub new_owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AcceptOwner>) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    let previous_owner = strategy.owner;
+    strategy.owner = strategy.pending_owner;
+    strategy.pending_owner = Pubkey::default();
+
+    emit!(OwnershipTransferred {
+        strategy: strategy.key(),
+        previous_owner,
+        new_owner: strategy.owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} owner changed from {} to {}", strategy.name_as_str(), previous_owner, strategy.owner);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::{DepositorPass, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::DepositorAdded;
+
+/// Issue a DepositorPass allowing `depositor` into a private strategy.
+/// Signed by the strategy owner or its attestor, who pays the pass rent.
+#[derive(Accounts)]
+#[instruction(depositor: Pubkey)]
+pub struct AddDepositor<'info> {
+    #[account(constraint = strategy.can_attest(&issuer.key()) @ KagemushaError::Unauthorized)]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        init,
+        payer = issuer,
+        space = DepositorPass::LEN,
+        seeds = [b"depositor_pass", strategy.key().as_ref(), depositor.as_ref()],
+        bump
+    )]
+    pub depositor_pass: Account<'info, DepositorPass>,
+
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddDepositor>, depositor: Pubkey) -> Result<()> {
+    let pass = &mut ctx.accounts.depositor_pass;
+    pass.strategy = ctx.accounts.strategy.key();
+    pass.depositor = depositor;
+    pass.issued_by = ctx.accounts.issuer.key();
+    pass.bump = ctx.bumps.depositor_pass;
+
+    emit!(DepositorAdded {
+        strategy: pass.strategy,
+        depositor,
+        issued_by: pass.issued_by,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Depositor {} allowed into strategy {}", depositor, ctx.accounts.strategy.name_as_str());
+
+    Ok(())
+}
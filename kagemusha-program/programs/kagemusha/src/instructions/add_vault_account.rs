@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+
+/// Create the strategy-owned token account for one composition mint.
+/// The account is a PDA whose token authority is the strategy itself.
+/// Mints of a pending composition need their account before
+/// `execute_weights`, which checks it by address. For a composition passed
+/// by a shareholder vote anyone can create them, so the owner can't hold up
+/// its execution.
+#[derive(Accounts)]
+pub struct AddVaultAccount<'info> {
+    #[account(mut)]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = strategy.basket_index(&mint.key()).is_some()
+            || strategy.pending_index(&mint.key()).is_some() @ KagemushaError::MintMismatch,
+        constraint = payer.key() == strategy.owner
+            || (strategy.pending_weights_governed && strategy.pending_index(&mint.key()).is_some())
+            @ KagemushaError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vault_token", strategy.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = strategy,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler(ctx: Context<AddVaultAccount>) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    let mint = ctx.accounts.mint.key();
+
+    if let Some(index) = strategy.basket_index(&mint) {
+        let vault_account = ctx.accounts.vault_token_account.key();
+        require!(
+            strategy.vault_token_accounts[index] == Pubkey::default()
+                || strategy.vault_token_accounts[index] == vault_account,
+            KagemushaError::InvalidVaultAccount
+        );
+        strategy.vault_token_accounts[index] = vault_account;
+    }
+
+    msg!("Kagemusha: Vault account {} created for mint {}", ctx.accounts.vault_token_account.key(), mint);
+
+    Ok(())
+}
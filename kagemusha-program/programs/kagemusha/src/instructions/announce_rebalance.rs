@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::RebalanceAnnounced;
+
+/// Announce a rebalance that moves the basket away from its target weights.
+/// It can run once the strategy's `weights_timelock` has elapsed; until then
+/// depositors can withdraw without exit fees. Replaces any rebalance already
+/// announced.
+#[derive(Accounts)]
+pub struct AnnounceRebalance<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<AnnounceRebalance>,
+    mint_in: Pubkey,
+    mint_out: Pubkey,
+    max_amount_in: u64,
+) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    require!(strategy.basket_index(&mint_in).is_some(), KagemushaError::MintMismatch);
+    require!(strategy.basket_index(&mint_out).is_some(), KagemushaError::MintMismatch);
+    require!(max_amount_in > 0, KagemushaError::InsufficientFunds);
+
+    let now = Clock::get()?.unix_timestamp;
+    let eta = now
+        .checked_add(strategy.weights_timelock)
+        .ok_or(KagemushaError::MathOverflow)?;
+    strategy.pending_rebalance_eta = eta;
+    strategy.pending_rebalance_mint_in = mint_in;
+    strategy.pending_rebalance_mint_out = mint_out;
+    strategy.pending_rebalance_max_in = max_amount_in;
+
+    emit!(RebalanceAnnounced {
+        strategy: strategy.key(),
+        mint_in,
+        mint_out,
+        max_amount_in,
+        eta,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Strategy {} off-target rebalance announced, executable at {}", strategy.name_as_str(), eta);
+
+    Ok(())
+}
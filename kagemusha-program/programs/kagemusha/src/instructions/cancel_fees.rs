@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::FeesCancelled;
+
+/// Withdraw the pending fee increase. Rates passed by a shareholder vote
+/// can't be cancelled.
+#[derive(Accounts)]
+pub struct CancelFees<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        constraint = strategy.pending_fees_eta != 0 @ KagemushaError::NoPendingFees,
+        constraint = !strategy.pending_fees_governed @ KagemushaError::GovernanceRequired
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelFees>) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.clear_pending_fees();
+
+    emit!(FeesCancelled {
+        strategy: strategy.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} fee increase cancelled", strategy.name_as_str());
+
+    Ok(())
+}
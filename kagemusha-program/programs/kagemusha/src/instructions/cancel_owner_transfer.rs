@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::OwnershipTransferCancelled;
+
+/// Withdraw a pending ownership transfer.
+#[derive(Accounts)]
+pub struct CancelOwnerTransfer<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        constraint = strategy.pending_owner != Pubkey::default() @ KagemushaError::NoPendingOwner
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelOwnerTransfer>) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    let pending_owner = strategy.pending_owner;
+    strategy.pending_owner = Pubkey::default();
+
+    emit!(OwnershipTransferCancelled {
+        strategy: strategy.key(),
+        owner: strategy.owner,
+        pending_owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} ownership transfer to {} cancelled", strategy.name_as_str(), pending_owner);
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::{Proposal, ProposalStatus, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::ProposalCancelled;
+
+/// Withdraw a proposal that has not been executed.
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(has_one = owner @ KagemushaError::Unauthorized)]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        mut,
+        has_one = strategy @ KagemushaError::Unauthorized
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(proposal.status == ProposalStatus::Voting, KagemushaError::ProposalNotActive);
+    proposal.status = ProposalStatus::Cancelled;
+
+    emit!(ProposalCancelled {
+        strategy: proposal.strategy,
+        proposal: proposal.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Proposal {} cancelled for strategy {}", proposal.id, ctx.accounts.strategy.name_as_str());
+
+    Ok(())
+}
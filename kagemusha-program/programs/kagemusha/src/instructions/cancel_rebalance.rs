@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::RebalanceAnnouncementCancelled;
+
+/// Withdraw an announced off-target rebalance.
+#[derive(Accounts)]
+pub struct CancelRebalance<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        constraint = strategy.pending_rebalance_eta != 0 @ KagemushaError::RebalanceNotAnnounced
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelRebalance>) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.clear_pending_rebalance();
+
+    emit!(RebalanceAnnouncementCancelled {
+        strategy: strategy.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} off-target rebalance cancelled", strategy.name_as_str());
+
+    Ok(())
+}
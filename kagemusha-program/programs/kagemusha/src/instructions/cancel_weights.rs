@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::WeightsCancelled;
+
+/// Withdraw the pending composition change. A composition passed by a
+/// shareholder vote can't be cancelled.
+#[derive(Accounts)]
+pub struct CancelWeights<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        constraint = strategy.pending_weights_eta != 0 @ KagemushaError::NoPendingWeights,
+        constraint = !strategy.pending_weights_governed @ KagemushaError::GovernanceRequired
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelWeights>) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.pending_weights_eta = 0;
+    strategy.pending_num_tokens = 0;
+    strategy.pending_target_weights = [0; 10];
+    strategy.pending_mints = [Pubkey::default(); 10];
+    strategy.pending_price_feeds = [Pubkey::default(); 10];
+
+    emit!(WeightsCancelled {
+        strategy: strategy.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} composition change cancelled", strategy.name_as_str());
+
+    Ok(())
+}
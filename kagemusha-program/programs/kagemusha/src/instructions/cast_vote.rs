@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{Proposal, ProposalStatus, StrategyVault, UserPosition, VoteRecord};
+use crate::errors::KagemushaError;
+use crate::events::VoteCast;
+
+/// Vote on an open proposal with `amount` share tokens. The tokens are locked
+/// in an escrow owned by the vote record until voting ends, so the same
+/// tokens can't vote twice; `reclaim_vote` returns them.
+///
+/// The weight can't exceed the voter's position as of the proposal's
+/// creation: a position that deposited since then can't vote, so shares
+/// minted after the snapshot carry no weight.
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(has_one = share_mint @ KagemushaError::MintMismatch)]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        mut,
+        has_one = strategy @ KagemushaError::Unauthorized
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(
+        init,
+        payer = voter,
+        seeds = [b"vote_escrow", vote_record.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = vote_record,
+    )]
+    pub vote_escrow: Account<'info, TokenAccount>,
+
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"position", strategy.key().as_ref(), voter.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = voter,
+    )]
+    pub voter_share_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CastVote>, amount: u64, approve: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(
+        proposal.status == ProposalStatus::Voting && now < proposal.voting_ends_at,
+        KagemushaError::VotingClosed
+    );
+    require!(amount > 0, KagemushaError::InsufficientFunds);
+    require!(
+        amount <= ctx.accounts.position.shares_held_before(proposal.created_at),
+        KagemushaError::VoteWeightNotSnapshotted
+    );
+    require!(
+        amount <= ctx.accounts.voter_share_account.amount,
+        KagemushaError::InsufficientFunds
+    );
+
+    // Lock the voting tokens until the vote is over
+    let transfer_accounts = Transfer {
+        from: ctx.accounts.voter_share_account.to_account_info(),
+        to: ctx.accounts.vote_escrow.to_account_info(),
+        authority: ctx.accounts.voter.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts),
+        amount,
+    )?;
+
+    if approve {
+        proposal.votes_for = proposal.votes_for.checked_add(amount).ok_or(KagemushaError::MathOverflow)?;
+    } else {
+        proposal.votes_against = proposal.votes_against.checked_add(amount).ok_or(KagemushaError::MathOverflow)?;
+    }
+
+    let vote_record = &mut ctx.accounts.vote_record;
+    vote_record.proposal = proposal.key();
+    vote_record.voter = ctx.accounts.voter.key();
+    vote_record.weight = amount;
+    vote_record.approve = approve;
+    vote_record.bump = ctx.bumps.vote_record;
+
+    emit!(VoteCast {
+        proposal: proposal.key(),
+        voter: vote_record.voter,
+        weight: amount,
+        approve,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: {} voted {} on proposal {} with {} shares", vote_record.voter, if approve { "for" } else { "against" }, proposal.id, amount);
+
+    Ok(())
+}
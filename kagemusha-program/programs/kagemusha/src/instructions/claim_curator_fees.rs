@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use crate::state::{ProtocolConfig, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::CuratorFeesClaimed;
+use crate::nav;
+
+/// Mint the curator's accrued fee shares and pay its swap fees to the
+/// strategy owner.
+///
+/// Remaining accounts: (vault token account, owner token account) for each
+/// composition token, in the order of the strategy's mints.
+#[derive(Accounts)]
+pub struct ClaimCuratorFees<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        has_one = share_mint @ KagemushaError::MintMismatch
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = owner,
+    )]
+    pub owner_share_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimCuratorFees<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.accrue_management_fee(ctx.accounts.share_mint.supply, now, &ctx.accounts.config)?;
+
+    let shares = strategy.curator_fee_shares;
+    let amounts = strategy.curator_swap_fees;
+    require!(
+        shares > 0 || amounts.iter().any(|&a| a > 0),
+        KagemushaError::ZeroShares
+    );
+    strategy.curator_fee_shares = 0;
+    strategy.curator_swap_fees = [0; 10];
+
+    let strategy = &ctx.accounts.strategy;
+    if shares > 0 {
+        let seeds: &[&[u8]] = &[
+            b"strategy",
+            strategy.creator.as_ref(),
+            strategy.name_seed(),
+            &[strategy.bump]
+        ];
+        let signer_seeds = &[seeds];
+
+        let mint_accounts = MintTo {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            to: ctx.accounts.owner_share_account.to_account_info(),
+            authority: strategy.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                mint_accounts,
+                signer_seeds,
+            ),
+            shares,
+        )?;
+    }
+
+    nav::pay_reserved_fees(
+        strategy,
+        &amounts,
+        ctx.remaining_accounts,
+        &strategy.owner,
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    emit!(CuratorFeesClaimed {
+        strategy: strategy.key(),
+        curator: ctx.accounts.owner.key(),
+        shares,
+        amounts,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Curator claimed {} fee shares from strategy {}", shares, strategy.name_as_str());
+
+    Ok(())
+}
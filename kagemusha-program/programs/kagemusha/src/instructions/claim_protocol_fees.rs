@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use crate::state::{ProtocolConfig, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::ProtocolFeesClaimed;
+use crate::nav;
+
+/// Pay the protocol's fee shares and swap fees held by a strategy to the
+/// treasury.
+///
+/// Remaining accounts: (vault token account, treasury token account) for each
+/// composition token, in the order of the strategy's mints.
+#[derive(Accounts)]
+pub struct ClaimProtocolFees<'info> {
+    #[account(
+        mut,
+        has_one = share_mint @ KagemushaError::MintMismatch
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = admin @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        constraint = treasury_share_account.owner == config.treasury @ KagemushaError::InvalidFeeAccount
+    )]
+    pub treasury_share_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimProtocolFees<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.accrue_management_fee(ctx.accounts.share_mint.supply, now, &ctx.accounts.config)?;
+
+    let shares = strategy.protocol_fee_shares;
+    let amounts = strategy.protocol_swap_fees;
+    require!(
+        shares > 0 || amounts.iter().any(|&a| a > 0),
+        KagemushaError::ZeroShares
+    );
+    strategy.protocol_fee_shares = 0;
+    strategy.protocol_swap_fees = [0; 10];
+
+    let strategy = &ctx.accounts.strategy;
+    if shares > 0 {
+        let seeds: &[&[u8]] = &[
+            b"strategy",
+            strategy.creator.as_ref(),
+            strategy.name_seed(),
+            &[strategy.bump]
+        ];
+        let signer_seeds = &[seeds];
+
+        let mint_accounts = MintTo {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            to: ctx.accounts.treasury_share_account.to_account_info(),
+            authority: strategy.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                mint_accounts,
+                signer_seeds,
+            ),
+            shares,
+        )?;
+    }
+
+    nav::pay_reserved_fees(
+        strategy,
+        &amounts,
+        ctx.remaining_accounts,
+        &ctx.accounts.config.treasury,
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    emit!(ProtocolFeesClaimed {
+        strategy: strategy.key(),
+        treasury: ctx.accounts.config.treasury,
+        shares,
+        amounts,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Protocol fees claimed from strategy {}", strategy.name_as_str());
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use crate::state::{ReferrerAccount, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::ReferrerFeesClaimed;
+
+/// Mint the referral fee shares earned in a strategy to the referrer.
+#[derive(Accounts)]
+pub struct ClaimReferrerFees<'info> {
+    #[account(
+        mut,
+        has_one = share_mint @ KagemushaError::MintMismatch
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", strategy.key().as_ref(), referrer.key().as_ref()],
+        bump = referrer_account.bump,
+        has_one = referrer @ KagemushaError::Unauthorized
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+
+    pub referrer: Signer<'info>,
+
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = referrer,
+    )]
+    pub referrer_share_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<ClaimReferrerFees>) -> Result<()> {
+    let shares = ctx.accounts.referrer_account.pending_shares;
+    require!(shares > 0, KagemushaError::ZeroShares);
+    ctx.accounts.referrer_account.pending_shares = 0;
+
+    // Pending referral shares move from the strategy's unminted total to the supply
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.referrer_fee_shares = strategy
+        .referrer_fee_shares
+        .checked_sub(shares)
+        .ok_or(KagemushaError::MathOverflow)?;
+
+    let seeds: &[&[u8]] = &[
+        b"strategy",
+        strategy.creator.as_ref(),
+        strategy.name_seed(),
+        &[strategy.bump]
+    ];
+    let signer_seeds = &[seeds];
+
+    let mint_accounts = MintTo {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        to: ctx.accounts.referrer_share_account.to_account_info(),
+        authority: strategy.to_account_info(),
+    };
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_accounts,
+            signer_seeds,
+        ),
+        shares,
+    )?;
+
+    emit!(ReferrerFeesClaimed {
+        strategy: strategy.key(),
+        referrer: ctx.accounts.referrer.key(),
+        shares,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Referrer claimed {} fee shares from strategy {}", shares, strategy.name_as_str());
+
+    Ok(())
+}
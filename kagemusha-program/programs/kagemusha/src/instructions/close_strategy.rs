@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use anchor_spl::token::{self, CloseAccount, Mint, SetAuthority, Token, TokenAccount, Transfer};
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::StrategyClosed;
+
+/// Retire a strategy with no shares outstanding and no unclaimed fees.
+/// Leftover dust in the vault token accounts and `vault_sol` is swept to the
+/// owner, the vault token accounts and the strategy are closed, and the rent
+/// is returned to the owner.
+///
+/// The SPL Token program can't close mint accounts, so the share mint stays
+/// open with its mint authority revoked.
+///
+/// Remaining accounts: (vault token account, owner token account) for each
+/// composition token that has a vault token account, in the order of the
+/// strategy's mints.
+#[derive(Accounts)]
+pub struct CloseStrategy<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        has_one = share_mint @ KagemushaError::MintMismatch,
+        close = owner
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: This is the vault's SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"vault_sol", strategy.key().as_ref()],
+        bump
+    )]
+    pub vault_sol: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, CloseStrategy<'info>>) -> Result<()> {
+    let strategy = &ctx.accounts.strategy;
+    let num_tokens = strategy.num_tokens as usize;
+
+    // Nothing may be owed to holders or fee recipients
+    require!(
+        strategy.total_shares(ctx.accounts.share_mint.supply)? == 0,
+        KagemushaError::StrategyNotEmpty
+    );
+    for i in 0..num_tokens {
+        require!(strategy.reserved_fees(i)? == 0, KagemushaError::StrategyNotEmpty);
+    }
+
+    let seeds: &[&[u8]] = &[
+        b"strategy",
+        strategy.creator.as_ref(),
+        strategy.name_seed(),
+        &[strategy.bump]
+    ];
+    let signer_seeds = &[seeds];
+
+    // Sweep and close each vault token account
+    let vault_accounts: Vec<Pubkey> = strategy.vault_token_accounts[..num_tokens]
+        .iter()
+        .copied()
+        .filter(|account| *account != Pubkey::default())
+        .collect();
+    require!(
+        ctx.remaining_accounts.len() >= vault_accounts.len() * 2,
+        KagemushaError::InvalidBasketAccounts
+    );
+    for (expected, pair) in vault_accounts.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        let (vault_info, owner_info) = (&pair[0], &pair[1]);
+        require!(vault_info.key() == *expected, KagemushaError::InvalidVaultAccount);
+
+        let vault_account = TokenAccount::try_deserialize(&mut &vault_info.data.borrow()[..])?;
+        if vault_account.amount > 0 {
+            let owner_account = TokenAccount::try_deserialize(&mut &owner_info.data.borrow()[..])?;
+            require!(owner_account.owner == strategy.owner, KagemushaError::Unauthorized);
+            require!(owner_account.mint == vault_account.mint, KagemushaError::MintMismatch);
+
+            let transfer_accounts = Transfer {
+                from: vault_info.clone(),
+                to: owner_info.clone(),
+                authority: strategy.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    transfer_accounts,
+                    signer_seeds,
+                ),
+                vault_account.amount,
+            )?;
+        }
+
+        let close_accounts = CloseAccount {
+            account: vault_info.clone(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: strategy.to_account_info(),
+        };
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_accounts,
+            signer_seeds,
+        ))?;
+    }
+
+    // No more shares can ever be minted
+    let authority_accounts = SetAuthority {
+        current_authority: strategy.to_account_info(),
+        account_or_mint: ctx.accounts.share_mint.to_account_info(),
+    };
+    token::set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            authority_accounts,
+            signer_seeds,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    // Empty vault_sol, rent included, to the owner
+    let lamports = ctx.accounts.vault_sol.lamports();
+    if lamports > 0 {
+        let strategy_key = strategy.key();
+        let vault_sol_seeds: &[&[u8]] = &[
+            b"vault_sol",
+            strategy_key.as_ref(),
+            &[ctx.bumps.vault_sol]
+        ];
+        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+            ctx.accounts.vault_sol.key,
+            ctx.accounts.owner.key,
+            lamports,
+        );
+        anchor_lang::solana_program::program::invoke_signed(
+            &transfer_ix,
+            &[
+                ctx.accounts.vault_sol.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            &[vault_sol_seeds],
+        )?;
+    }
+
+    emit!(StrategyClosed {
+        strategy: strategy.key(),
+        owner: strategy.owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} closed", strategy.name_as_str());
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::{MintRegistry, Proposal, ProposalAction, ProposalStatus, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::ProposalCreated;
+
+/// Put a change to a governed strategy to a shareholder vote. The share
+/// supply is snapshotted as the quorum base. Votes are capped at each
+/// voter's position as of now, and positions that deposit later can't vote.
+///
+/// Remaining accounts: for a weights proposal, the MintRegistry entry of each
+/// proposed mint, in the order of `mints`.
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        has_one = share_mint @ KagemushaError::MintMismatch
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Proposal::LEN,
+        seeds = [b"proposal", strategy.key().as_ref(), &strategy.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    require!(
+        ctx.accounts.strategy.governance_enabled(),
+        KagemushaError::GovernanceDisabled
+    );
+    if let ProposalAction::Weights { target_weights, mints, price_feeds } = &action {
+        StrategyVault::validate_composition(target_weights, mints, price_feeds)?;
+        MintRegistry::verify_composition(ctx.remaining_accounts, mints, price_feeds)?;
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let strategy = &mut ctx.accounts.strategy;
+    let voting_ends_at = now
+        .checked_add(strategy.governance_voting_period)
+        .ok_or(KagemushaError::MathOverflow)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.strategy = strategy.key();
+    proposal.id = strategy.proposal_count;
+    proposal.proposer = ctx.accounts.owner.key();
+    proposal.action = action;
+    proposal.created_at = now;
+    proposal.voting_ends_at = voting_ends_at;
+    proposal.snapshot_supply = ctx.accounts.share_mint.supply;
+    proposal.votes_for = 0;
+    proposal.votes_against = 0;
+    proposal.status = ProposalStatus::Voting;
+    proposal.bump = ctx.bumps.proposal;
+
+    strategy.proposal_count = strategy
+        .proposal_count
+        .checked_add(1)
+        .ok_or(KagemushaError::MathOverflow)?;
+
+    emit!(ProposalCreated {
+        strategy: strategy.key(),
+        proposal: proposal.key(),
+        id: proposal.id,
+        voting_ends_at,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Proposal {} created for strategy {}, voting until {}", proposal.id, strategy.name_as_str(), voting_ends_at);
+
+    Ok(())
+}
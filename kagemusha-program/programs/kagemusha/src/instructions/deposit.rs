@@ -1,15 +1,25 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::state::{StrategyVault, UserPosition};
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use crate::state::{DepositorPass, ProtocolConfig, ReferrerAccount, StrategyVault, UserPosition};
 use crate::errors::KagemushaError;
+use crate::events::Deposited;
+use crate::fees;
+use crate::nav;
 
+/// Deposit SPL tokens into one of the vault's basket token accounts.
+///
+/// Remaining accounts: (vault token account, mint, price feed) for each
+/// composition token, used to price the deposit against the vault's NAV.
 #[derive(Accounts)]
 pub struct Deposit<'info> {
+    #[account(mut)]
+    pub strategy: Account<'info, StrategyVault>,
+
     #[account(
-        mut,
-        has_one = owner @ KagemushaError::Unauthorized
+        seeds = [b"protocol_config"],
+        bump = config.bump
     )]
-    pub strategy: Account<'info, StrategyVault>,
+    pub config: Account<'info, ProtocolConfig>,
     
     #[account(
         init_if_needed,
@@ -22,22 +32,108 @@ pub struct Deposit<'info> {
     
     #[account(mut)]
     pub user: Signer<'info>,
-    
-    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"depositor_pass", strategy.key().as_ref(), user.key().as_ref()],
+        bump = depositor_pass.bump
+    )]
+    pub depositor_pass: Option<Account<'info, DepositorPass>>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", strategy.key().as_ref(), referrer_account.referrer.as_ref()],
+        bump = referrer_account.bump
+    )]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
     
     #[account(mut)]
     pub user_token_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = strategy.vault_account_for(&vault_token_account.mint) == Some(vault_token_account.key()) @ KagemushaError::InvalidVaultAccount
+    )]
     pub vault_token_account: Account<'info, TokenAccount>,
     
+    /// CHECK: This is the vault's SOL account (PDA), read for NAV only
+    #[account(
+        seeds = [b"vault_sol", strategy.key().as_ref()],
+        bump
+    )]
+    pub vault_sol: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = strategy.share_mint @ KagemushaError::MintMismatch
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = user,
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
+    
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-    require!(ctx.accounts.strategy.is_active, KagemushaError::StrategyInactive);
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+    amount: u64,
+    referrer: Option<Pubkey>,
+) -> Result<()> {
+    require!(ctx.accounts.strategy.status.accepts_deposits(), KagemushaError::StrategyInactive);
+    require!(!ctx.accounts.config.deposits_paused, KagemushaError::ProtocolPaused);
     require!(amount > 0, KagemushaError::InsufficientFunds);
+    require!(
+        !ctx.accounts.strategy.is_private || ctx.accounts.depositor_pass.is_some(),
+        KagemushaError::DepositorNotAllowed
+    );
+    
+    // Accrue fees owed up to now before pricing shares
+    let now = Clock::get()?.unix_timestamp;
+    let minted_supply = ctx.accounts.share_mint.supply;
+    ctx.accounts.strategy.accrue_management_fee(minted_supply, now, &ctx.accounts.config)?;
+    
+    // Price the deposit against the vault's NAV before any funds move
+    let basket = nav::load_basket(&ctx.accounts.strategy, &ctx.accounts.config, ctx.remaining_accounts)?;
+    let asset = basket
+        .iter()
+        .find(|asset| asset.info.key() == ctx.accounts.vault_token_account.key())
+        .ok_or(KagemushaError::InvalidVaultAccount)?;
+    let value = asset.price.usd_value(amount, asset.decimals)?;
+    let nav_before = nav::compute_nav(&basket, nav::liquid_lamports(&ctx.accounts.vault_sol)?)?;
+    ctx.accounts.strategy.crystallize_performance_fee(
+        nav_before,
+        minted_supply,
+        now,
+        false,
+        &ctx.accounts.config,
+    )?;
+    let total_shares = ctx.accounts.strategy.total_shares(minted_supply)?;
+    let gross_shares = nav::shares_for_deposit(value, total_shares, nav_before)?;
+    
+    // Record the referrer on a new position; it earns part of the position's fees
+    ctx.accounts.position.set_referrer(referrer, &ctx.accounts.user.key())?;
+    let referred = ctx
+        .accounts
+        .position
+        .check_referrer(ctx.accounts.referrer_account.as_deref())?;
+    
+    // Entry fee is taken in shares and split between the fee recipients
+    let entry_fee_bps = ctx.accounts.strategy.entry_fee_bps;
+    let (shares, fee_split) = ctx.accounts.strategy.charge_share_fee(
+        gross_shares,
+        entry_fee_bps,
+        &ctx.accounts.config,
+        referred,
+    )?;
+    require!(shares > 0, KagemushaError::ZeroShares);
+    if let Some(referrer_account) = ctx.accounts.referrer_account.as_mut() {
+        referrer_account.credit(fee_split.referrer)?;
+    }
     
     // Transfer tokens to vault
     let cpi_accounts = Transfer {
@@ -48,20 +144,57 @@ pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     token::transfer(cpi_ctx, amount)?;
     
+    // Mint share tokens to the depositor
+    let strategy = &ctx.accounts.strategy;
+    let seeds: &[&[u8]] = &[
+        b"strategy",
+        strategy.creator.as_ref(),
+        strategy.name_seed(),
+        &[strategy.bump]
+    ];
+    let signer_seeds = &[seeds];
+    
+    let mint_accounts = MintTo {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        to: ctx.accounts.user_share_account.to_account_info(),
+        authority: strategy.to_account_info(),
+    };
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_accounts,
+            signer_seeds,
+        ),
+        shares,
+    )?;
+    
     // Update position
     let position = &mut ctx.accounts.position;
     position.vault = ctx.accounts.strategy.key();
     position.user = ctx.accounts.user.key();
-    position.lp_shares = position.lp_shares.checked_add(amount).unwrap();
-    position.deposit_time = Clock::get()?.unix_timestamp;
-    position.entry_value = position.entry_value.checked_add(amount).unwrap();
+    position.lp_shares = position.lp_shares.checked_add(shares).ok_or(KagemushaError::MathOverflow)?;
+    position.deposit_time = now;
+    position.entry_value = position.entry_value.checked_add(value).ok_or(KagemushaError::MathOverflow)?;
     position.bump = ctx.bumps.position;
     
-    // Update vault TVL
+    // Update vault TVL to the post-deposit NAV
     let strategy = &mut ctx.accounts.strategy;
-    strategy.tvl = strategy.tvl.checked_add(amount).unwrap();
+    if total_shares == 0 {
+        // First shares are minted 1:1 with value, so the mark starts at 1.0
+        strategy.high_water_mark = fees::SHARE_PRICE_SCALE;
+    }
+    strategy.tvl = nav_before.checked_add(value).ok_or(KagemushaError::MathOverflow)?;
+    
+    emit!(Deposited {
+        strategy: strategy.key(),
+        user: ctx.accounts.user.key(),
+        value,
+        shares,
+        fees: fee_split,
+        timestamp: now,
+    });
     
-    msg!("Kagemusha: Deposited {} to strategy {}", amount, strategy.name_as_str());
+    msg!("Kagemusha: Deposited {} for {} shares to strategy {}", amount, shares, strategy.name_as_str());
     
     Ok(())
 }
@@ -1,14 +1,28 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{StrategyVault, UserPosition};
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use crate::state::{DepositorPass, ProtocolConfig, ReferrerAccount, StrategyVault, UserPosition};
 use crate::errors::KagemushaError;
+use crate::events::Deposited;
+use crate::fees;
+use crate::nav;
 
 /// Deposit native SOL into a strategy vault
 /// This is a simpler version that doesn't require token accounts
+///
+/// Remaining accounts: (vault token account, mint, price feed) for each
+/// composition token, used to price the deposit against the vault's NAV.
+/// The basket must include wrapped SOL, whose price values the deposit.
 #[derive(Accounts)]
 pub struct DepositSol<'info> {
     #[account(mut)]
     pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
     
     #[account(
         init_if_needed,
@@ -21,6 +35,19 @@ pub struct DepositSol<'info> {
     
     #[account(mut)]
     pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"depositor_pass", strategy.key().as_ref(), user.key().as_ref()],
+        bump = depositor_pass.bump
+    )]
+    pub depositor_pass: Option<Account<'info, DepositorPass>>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", strategy.key().as_ref(), referrer_account.referrer.as_ref()],
+        bump = referrer_account.bump
+    )]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
     
     /// CHECK: This is the vault's SOL account (PDA)
     #[account(
@@ -30,15 +57,77 @@ pub struct DepositSol<'info> {
     )]
     pub vault_sol: AccountInfo<'info>,
     
+    #[account(
+        mut,
+        address = strategy.share_mint @ KagemushaError::MintMismatch
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = user,
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<DepositSol>, amount: u64) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositSol<'info>>,
+    amount: u64,
+    referrer: Option<Pubkey>,
+) -> Result<()> {
+    require!(ctx.accounts.strategy.status.accepts_deposits(), KagemushaError::StrategyInactive);
+    require!(!ctx.accounts.config.deposits_paused, KagemushaError::ProtocolPaused);
+    require!(amount > 0, KagemushaError::InsufficientFunds);
+    require!(
+        !ctx.accounts.strategy.is_private || ctx.accounts.depositor_pass.is_some(),
+        KagemushaError::DepositorNotAllowed
+    );
+    
+    // Accrue fees owed up to now before pricing shares
+    let now = Clock::get()?.unix_timestamp;
+    let minted_supply = ctx.accounts.share_mint.supply;
+    ctx.accounts.strategy.accrue_management_fee(minted_supply, now, &ctx.accounts.config)?;
+    
     let strategy = &ctx.accounts.strategy;
     
-    // Check strategy is active
-    require!(strategy.is_active, KagemushaError::StrategyInactive);
-    require!(amount > 0, KagemushaError::InsufficientFunds);
+    // Price the deposit against the vault's NAV before any funds move
+    let basket = nav::load_basket(strategy, &ctx.accounts.config, ctx.remaining_accounts)?;
+    let value = nav::sol_value(&basket, amount)?;
+    let nav_before = nav::compute_nav(&basket, nav::liquid_lamports(&ctx.accounts.vault_sol)?)?;
+    ctx.accounts.strategy.crystallize_performance_fee(
+        nav_before,
+        minted_supply,
+        now,
+        false,
+        &ctx.accounts.config,
+    )?;
+    let total_shares = ctx.accounts.strategy.total_shares(minted_supply)?;
+    let gross_shares = nav::shares_for_deposit(value, total_shares, nav_before)?;
+    
+    // Record the referrer on a new position; it earns part of the position's fees
+    ctx.accounts.position.set_referrer(referrer, &ctx.accounts.user.key())?;
+    let referred = ctx
+        .accounts
+        .position
+        .check_referrer(ctx.accounts.referrer_account.as_deref())?;
+    
+    // Entry fee is taken in shares and split between the fee recipients
+    let entry_fee_bps = ctx.accounts.strategy.entry_fee_bps;
+    let (shares, fee_split) = ctx.accounts.strategy.charge_share_fee(
+        gross_shares,
+        entry_fee_bps,
+        &ctx.accounts.config,
+        referred,
+    )?;
+    require!(shares > 0, KagemushaError::ZeroShares);
+    if let Some(referrer_account) = ctx.accounts.referrer_account.as_mut() {
+        referrer_account.credit(fee_split.referrer)?;
+    }
+    let strategy = &ctx.accounts.strategy;
     
     // Transfer SOL from user to vault
     let cpi_context = CpiContext::new(
@@ -50,20 +139,56 @@ pub fn handler(ctx: Context<DepositSol>, amount: u64) -> Result<()> {
     );
     system_program::transfer(cpi_context, amount)?;
     
+    // Mint share tokens to the depositor
+    let seeds: &[&[u8]] = &[
+        b"strategy",
+        strategy.creator.as_ref(),
+        strategy.name_seed(),
+        &[strategy.bump]
+    ];
+    let signer_seeds = &[seeds];
+    
+    let mint_accounts = MintTo {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        to: ctx.accounts.user_share_account.to_account_info(),
+        authority: strategy.to_account_info(),
+    };
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_accounts,
+            signer_seeds,
+        ),
+        shares,
+    )?;
+    
     // Update user position
     let position = &mut ctx.accounts.position;
     position.vault = ctx.accounts.strategy.key();
     position.user = ctx.accounts.user.key();
-    position.lp_shares = position.lp_shares.checked_add(amount).unwrap();
-    position.deposit_time = Clock::get()?.unix_timestamp;
-    position.entry_value = position.entry_value.checked_add(amount).unwrap();
+    position.lp_shares = position.lp_shares.checked_add(shares).ok_or(KagemushaError::MathOverflow)?;
+    position.deposit_time = now;
+    position.entry_value = position.entry_value.checked_add(value).ok_or(KagemushaError::MathOverflow)?;
     position.bump = ctx.bumps.position;
     
-    // Update vault TVL
+    // Update vault TVL to the post-deposit NAV
     let strategy = &mut ctx.accounts.strategy;
-    strategy.tvl = strategy.tvl.checked_add(amount).unwrap();
+    if total_shares == 0 {
+        // First shares are minted 1:1 with value, so the mark starts at 1.0
+        strategy.high_water_mark = fees::SHARE_PRICE_SCALE;
+    }
+    strategy.tvl = nav_before.checked_add(value).ok_or(KagemushaError::MathOverflow)?;
+    
+    emit!(Deposited {
+        strategy: strategy.key(),
+        user: ctx.accounts.user.key(),
+        value,
+        shares,
+        fees: fee_split,
+        timestamp: now,
+    });
     
-    msg!("Kagemusha: Deposited {} lamports to strategy {}", amount, strategy.name_as_str());
+    msg!("Kagemusha: Deposited {} lamports for {} shares to strategy {}", amount, shares, strategy.name_as_str());
     
     Ok(())
 }
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::{ProtocolConfig, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::FeesExecuted;
+
+/// Apply the pending fee increase once its timelock has elapsed. Signed by
+/// the owner, or by anyone for rates passed by a shareholder vote.
+/// Management fees owed at the old rate are accrued first.
+#[derive(Accounts)]
+pub struct ExecuteFees<'info> {
+    #[account(
+        mut,
+        has_one = share_mint @ KagemushaError::MintMismatch,
+        constraint = !strategy.frozen @ KagemushaError::StrategyFrozen,
+        constraint = strategy.pending_fees_governed || authority.key() == strategy.owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub share_mint: Account<'info, Mint>,
+}
+
+pub fn handler(ctx: Context<ExecuteFees>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let strategy = &mut ctx.accounts.strategy;
+    require!(strategy.pending_fees_eta != 0, KagemushaError::NoPendingFees);
+    require!(now >= strategy.pending_fees_eta, KagemushaError::TimelockNotElapsed);
+
+    let params = strategy.pending_fees.clone();
+    strategy.accrue_management_fee(ctx.accounts.share_mint.supply, now, &ctx.accounts.config)?;
+    strategy.apply_fees(&params, &ctx.accounts.config)?;
+    strategy.clear_pending_fees();
+
+    emit!(FeesExecuted {
+        strategy: strategy.key(),
+        fees: params,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Strategy {} fees updated", strategy.name_as_str());
+    msg!("  Management Fee: {} bps/year", strategy.management_fee_bps);
+    msg!("  Performance Fee: {} bps every {}s", strategy.performance_fee_bps, strategy.performance_fee_interval);
+    msg!("  Entry/Exit Fee: {}/{} bps", strategy.entry_fee_bps, strategy.exit_fee_bps);
+    msg!("  Referrer Share: {} bps", strategy.referrer_fee_share_bps);
+
+    Ok(())
+}
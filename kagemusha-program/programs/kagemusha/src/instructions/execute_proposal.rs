@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::{MintRegistry, Proposal, ProposalAction, ProposalStatus, ProtocolConfig, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::{FeesProposed, ProposalExecuted, RebalancerChanged, WeightsProposed};
+
+/// Apply a proposal once voting has ended and it reached the strategy's
+/// quorum and threshold. Callable by anyone. A weights proposal stages the
+/// composition, which still goes through the weights timelock; anyone can
+/// then apply it with `execute_weights` and the owner can't cancel it. Fee
+/// increases are staged the same way for `execute_fees`; fee cuts apply at
+/// once.
+///
+/// Remaining accounts: for a weights proposal, the MintRegistry entry of each
+/// proposed mint, in the order of `mints`.
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        mut,
+        has_one = share_mint @ KagemushaError::MintMismatch,
+        constraint = !strategy.frozen @ KagemushaError::StrategyFrozen
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        mut,
+        has_one = strategy @ KagemushaError::Unauthorized
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub share_mint: Account<'info, Mint>,
+}
+
+pub fn handler(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &ctx.accounts.proposal;
+    require!(proposal.status == ProposalStatus::Voting, KagemushaError::ProposalNotActive);
+    require!(now >= proposal.voting_ends_at, KagemushaError::VotingNotEnded);
+    let strategy = &ctx.accounts.strategy;
+    require!(
+        proposal.passed(strategy.governance_quorum_bps, strategy.governance_threshold_bps)?,
+        KagemushaError::ProposalNotPassed
+    );
+
+    let strategy = &mut ctx.accounts.strategy;
+    match &proposal.action {
+        ProposalAction::Weights { target_weights, mints, price_feeds } => {
+            MintRegistry::verify_composition(ctx.remaining_accounts, mints, price_feeds)?;
+            let eta = strategy.stage_weights(target_weights, mints, price_feeds, true, now)?;
+
+            emit!(WeightsProposed {
+                strategy: strategy.key(),
+                num_tokens: strategy.pending_num_tokens,
+                target_weights: strategy.pending_target_weights,
+                mints: strategy.pending_mints,
+                eta,
+                timestamp: now,
+            });
+        }
+        ProposalAction::Fees(params) if strategy.raises_fees(params) => {
+            let eta = strategy.stage_fees(params, true, now, &ctx.accounts.config)?;
+
+            emit!(FeesProposed {
+                strategy: strategy.key(),
+                fees: params.clone(),
+                eta,
+                timestamp: now,
+            });
+        }
+        ProposalAction::Fees(params) => {
+            strategy.accrue_management_fee(ctx.accounts.share_mint.supply, now, &ctx.accounts.config)?;
+            strategy.apply_fees(params, &ctx.accounts.config)?;
+        }
+        ProposalAction::Rebalancer(rebalancer) => {
+            let previous_rebalancer = strategy.rebalancer;
+            strategy.rebalancer = *rebalancer;
+
+            emit!(RebalancerChanged {
+                strategy: strategy.key(),
+                previous_rebalancer,
+                rebalancer: *rebalancer,
+                timestamp: now,
+            });
+        }
+    }
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.status = ProposalStatus::Executed;
+
+    emit!(ProposalExecuted {
+        strategy: strategy.key(),
+        proposal: proposal.key(),
+        votes_for: proposal.votes_for,
+        votes_against: proposal.votes_against,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Proposal {} executed for strategy {}", proposal.id, strategy.name_as_str());
+
+    Ok(())
+}
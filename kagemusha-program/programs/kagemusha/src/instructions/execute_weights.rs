@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount};
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::WeightsExecuted;
+
+/// Apply the pending composition once its timelock has elapsed. Signed by
+/// the owner, or by anyone for a composition passed by a shareholder vote.
+///
+/// Vault token accounts and reserved swap fees follow their mint to its new
+/// slot. Mints new to the basket must already have their
+/// `["vault_token", strategy, mint]` account, created with `add_vault_account`,
+/// so deposits and withdrawals keep working once the composition changes.
+///
+/// Remaining accounts: the vault token account of each mint leaving the
+/// basket, in the order of the current mints (each must be empty), then the
+/// vault token account of each mint joining it, in the order of the pending
+/// mints.
+#[derive(Accounts)]
+pub struct ExecuteWeights<'info> {
+    #[account(
+        mut,
+        constraint = strategy.pending_weights_governed || authority.key() == strategy.owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ExecuteWeights>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let strategy = &mut ctx.accounts.strategy;
+    require!(strategy.pending_weights_eta != 0, KagemushaError::NoPendingWeights);
+    require!(now >= strategy.pending_weights_eta, KagemushaError::TimelockNotElapsed);
+
+    // Tokens leaving the basket must hold nothing, fees included
+    let num_tokens = strategy.num_tokens as usize;
+    let mut remaining_accounts = ctx.remaining_accounts.iter();
+    for i in 0..num_tokens {
+        if strategy.pending_index(&strategy.mints[i]).is_some()
+            || strategy.vault_token_accounts[i] == Pubkey::default()
+        {
+            continue;
+        }
+        let info = remaining_accounts
+            .next()
+            .ok_or(KagemushaError::InvalidBasketAccounts)?;
+        require!(
+            info.key() == strategy.vault_token_accounts[i],
+            KagemushaError::InvalidVaultAccount
+        );
+        let vault_account = TokenAccount::try_deserialize(&mut &info.data.borrow()[..])?;
+        require!(
+            vault_account.amount == 0 && strategy.reserved_fees(i)? == 0,
+            KagemushaError::RemovedTokenNotEmpty
+        );
+    }
+
+    // Carry vault accounts and reserved fees over to the new slots
+    let strategy_key = strategy.key();
+    let mut vault_token_accounts = [Pubkey::default(); 10];
+    let mut protocol_swap_fees = [0u64; 10];
+    let mut curator_swap_fees = [0u64; 10];
+    for j in 0..strategy.pending_num_tokens as usize {
+        let mint = strategy.pending_mints[j];
+        if let Some(i) = strategy.basket_index(&mint) {
+            vault_token_accounts[j] = strategy.vault_token_accounts[i];
+            protocol_swap_fees[j] = strategy.protocol_swap_fees[i];
+            curator_swap_fees[j] = strategy.curator_swap_fees[i];
+        } else {
+            let vault_account = Pubkey::find_program_address(
+                &[b"vault_token", strategy_key.as_ref(), mint.as_ref()],
+                &crate::ID,
+            ).0;
+            let info = remaining_accounts
+                .next()
+                .ok_or(KagemushaError::InvalidBasketAccounts)?;
+            require!(info.key() == vault_account, KagemushaError::InvalidVaultAccount);
+            require!(info.owner == &token::ID, KagemushaError::InvalidVaultAccount);
+            let token_account = TokenAccount::try_deserialize(&mut &info.data.borrow()[..])?;
+            require!(token_account.owner == strategy_key, KagemushaError::InvalidVaultAccount);
+            require!(token_account.mint == mint, KagemushaError::MintMismatch);
+            vault_token_accounts[j] = vault_account;
+        }
+    }
+
+    strategy.num_tokens = strategy.pending_num_tokens;
+    strategy.target_weights = strategy.pending_target_weights;
+    strategy.mints = strategy.pending_mints;
+    strategy.price_feeds = strategy.pending_price_feeds;
+    strategy.vault_token_accounts = vault_token_accounts;
+    strategy.protocol_swap_fees = protocol_swap_fees;
+    strategy.curator_swap_fees = curator_swap_fees;
+
+    strategy.pending_weights_eta = 0;
+    strategy.pending_num_tokens = 0;
+    strategy.pending_target_weights = [0; 10];
+    strategy.pending_mints = [Pubkey::default(); 10];
+    strategy.pending_price_feeds = [Pubkey::default(); 10];
+    strategy.pending_weights_governed = false;
+
+    emit!(WeightsExecuted {
+        strategy: strategy_key,
+        num_tokens: strategy.num_tokens,
+        target_weights: strategy.target_weights,
+        mints: strategy.mints,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Strategy {} composition updated to {} tokens", strategy.name_as_str(), strategy.num_tokens);
+
+    Ok(())
+}
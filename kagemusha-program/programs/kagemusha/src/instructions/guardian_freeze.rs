@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, StrategyStatus, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::StrategyFrozen;
+
+/// Freeze a strategy into withdraw-only mode. Deposits and rebalances stop,
+/// withdrawals stay open, and neither the owner nor the guardian can lift the
+/// freeze: only the protocol admin can, with `unfreeze_strategy`.
+#[derive(Accounts)]
+pub struct GuardianFreeze<'info> {
+    #[account(mut)]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = guardian @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub guardian: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<GuardianFreeze>) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    let previous_status = strategy.status;
+    if previous_status != StrategyStatus::Shutdown {
+        strategy.status = StrategyStatus::WithdrawOnly;
+    }
+    strategy.frozen = true;
+
+    emit!(StrategyFrozen {
+        strategy: strategy.key(),
+        guardian: ctx.accounts.guardian.key(),
+        previous_status,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} frozen by guardian {}", strategy.name_as_str(), ctx.accounts.guardian.key());
+
+    Ok(())
+}
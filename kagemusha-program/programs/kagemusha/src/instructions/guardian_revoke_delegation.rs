@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::{Delegation, ProtocolConfig, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::DelegationRevoked;
+
+/// Close a session delegation on behalf of the protocol. The rent goes back
+/// to the strategy owner who paid it.
+#[derive(Accounts)]
+pub struct GuardianRevokeDelegation<'info> {
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        mut,
+        seeds = [b"delegation", strategy.key().as_ref(), delegation.delegate.as_ref()],
+        bump = delegation.bump,
+        close = owner
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    /// CHECK: Strategy owner, only receives the delegation's rent
+    #[account(
+        mut,
+        address = strategy.owner @ KagemushaError::Unauthorized
+    )]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = guardian @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub guardian: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<GuardianRevokeDelegation>) -> Result<()> {
+    emit!(DelegationRevoked {
+        strategy: ctx.accounts.strategy.key(),
+        delegate: ctx.accounts.delegation.delegate,
+        revoked_by: ctx.accounts.guardian.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Kagemusha: Guardian revoked delegate {} of strategy {}",
+        ctx.accounts.delegation.delegate,
+        ctx.accounts.strategy.name_as_str()
+    );
+
+    Ok(())
+}
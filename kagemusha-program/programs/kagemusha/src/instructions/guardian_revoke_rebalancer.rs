@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::RebalancerChanged;
+
+/// Remove a strategy's delegated rebalancer, e.g. a compromised AI agent key.
+#[derive(Accounts)]
+pub struct GuardianRevokeRebalancer<'info> {
+    #[account(mut)]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = guardian @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub guardian: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<GuardianRevokeRebalancer>) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    let previous_rebalancer = strategy.rebalancer;
+    strategy.rebalancer = Pubkey::default();
+
+    emit!(RebalancerChanged {
+        strategy: strategy.key(),
+        previous_rebalancer,
+        rebalancer: Pubkey::default(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Guardian revoked rebalancer {} of strategy {}", previous_rebalancer, strategy.name_as_str());
+
+    Ok(())
+}
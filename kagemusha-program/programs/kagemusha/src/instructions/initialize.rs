@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
-use crate::state::StrategyVault;
+use anchor_spl::token::{Mint, Token};
+use crate::state::{MintRegistry, StrategyStatus, StrategyVault};
 use crate::errors::KagemushaError;
 
+/// Remaining accounts: the MintRegistry entry of each composition mint, in
+/// the order of `mints`.
 #[derive(Accounts)]
 #[instruction(name: String, strategy_type: u8)]
 pub struct InitializeStrategy<'info> {
@@ -14,10 +17,23 @@ pub struct InitializeStrategy<'info> {
     )]
     pub strategy: Account<'info, StrategyVault>,
     
+    /// Share token of the vault, minted on deposit and burned on withdraw
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"share_mint", strategy.key().as_ref()],
+        bump,
+        mint::decimals = StrategyVault::SHARE_DECIMALS,
+        mint::authority = strategy,
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
     #[account(mut)]
     pub owner: Signer<'info>,
     
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn handler(
@@ -25,13 +41,14 @@ pub fn handler(
     name: String,
     strategy_type: u8,
     target_weights: Vec<u16>,
+    mints: Vec<Pubkey>,
+    price_feeds: Vec<Pubkey>,
 ) -> Result<()> {
     // Validate inputs
     require!(name.len() <= 32, KagemushaError::NameTooLong);
     require!(strategy_type <= 2, KagemushaError::InvalidStrategyType);
-    
-    let weight_sum: u32 = target_weights.iter().map(|&w| w as u32).sum();
-    require!(weight_sum == 10000, KagemushaError::InvalidWeightSum);
+    StrategyVault::validate_composition(&target_weights, &mints, &price_feeds)?;
+    MintRegistry::verify_composition(ctx.remaining_accounts, &mints, &price_feeds)?;
     
     let strategy = &mut ctx.accounts.strategy;
     
@@ -46,16 +63,62 @@ pub fn handler(
         weights[i] = w;
     }
     
+    // Copy mints and price feeds into fixed-size arrays, aligned with weights
+    let mut basket_mints = [Pubkey::default(); 10];
+    basket_mints[..mints.len()].copy_from_slice(&mints);
+    let mut feeds = [Pubkey::default(); 10];
+    feeds[..price_feeds.len()].copy_from_slice(&price_feeds);
+    
     strategy.owner = ctx.accounts.owner.key();
     strategy.name = name_bytes;
     strategy.strategy_type = strategy_type;
     strategy.target_weights = weights;
     strategy.num_tokens = target_weights.len() as u8;
-    strategy.is_active = true;
+    strategy.status = StrategyStatus::Active;
     strategy.tvl = 0;
     strategy.fees_collected = 0;
-    strategy.last_rebalance = Clock::get()?.unix_timestamp;
+    let now = Clock::get()?.unix_timestamp;
+    strategy.last_rebalance = now;
     strategy.bump = ctx.bumps.strategy;
+    strategy.share_mint = ctx.accounts.share_mint.key();
+    strategy.price_feeds = feeds;
+    strategy.mints = basket_mints;
+    // Vault token accounts are created per mint with add_vault_account
+    strategy.vault_token_accounts = [Pubkey::default(); 10];
+    strategy.is_private = false;
+    strategy.management_fee_bps = 0;
+    strategy.last_fee_accrual = now;
+    strategy.curator_fee_shares = 0;
+    strategy.performance_fee_bps = 0;
+    strategy.performance_fee_interval = 0;
+    strategy.high_water_mark = 0;
+    strategy.last_crystallization = now;
+    strategy.performance_fees_collected = 0;
+    strategy.protocol_swap_fees = [0; 10];
+    strategy.entry_fee_bps = 0;
+    strategy.exit_fee_bps = 0;
+    strategy.protocol_fee_shares = 0;
+    strategy.curator_swap_fees = [0; 10];
+    strategy.referrer_fee_share_bps = 0;
+    strategy.referrer_fee_shares = 0;
+    strategy.creator = ctx.accounts.owner.key();
+    strategy.pending_owner = Pubkey::default();
+    strategy.rebalancer = Pubkey::default();
+    strategy.weights_timelock = StrategyVault::MIN_WEIGHTS_TIMELOCK;
+    strategy.pending_weights_eta = 0;
+    strategy.pending_num_tokens = 0;
+    strategy.pending_target_weights = [0; 10];
+    strategy.pending_mints = [Pubkey::default(); 10];
+    strategy.pending_price_feeds = [Pubkey::default(); 10];
+    strategy.clear_pending_rebalance();
+    strategy.frozen = false;
+    strategy.attestor = Pubkey::default();
+    strategy.governance_quorum_bps = 0;
+    strategy.governance_threshold_bps = 0;
+    strategy.governance_voting_period = 0;
+    strategy.proposal_count = 0;
+    strategy.pending_weights_governed = false;
+    strategy.clear_pending_fees();
     
     msg!("Kagemusha: Strategy '{}' initialized by {} (Jito Bundle)", name, ctx.accounts.owner.key());
     
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::program::Kagemusha;
+use crate::state::{ProtocolConfig, ProtocolConfigParams};
+use crate::errors::KagemushaError;
+
+/// Create the ProtocolConfig singleton.
+/// Only the program's upgrade authority can do this, and it becomes the admin.
+#[derive(Accounts)]
+pub struct InitializeProtocol<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolConfig::LEN,
+        seeds = [b"protocol_config"],
+        bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ KagemushaError::Unauthorized
+    )]
+    pub program: Program<'info, Kagemusha>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ KagemushaError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeProtocol>, params: ProtocolConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.bump = ctx.bumps.config;
+    config.apply(&params)?;
+
+    msg!("Kagemusha: Protocol config initialized, admin {}", config.admin);
+
+    Ok(())
+}
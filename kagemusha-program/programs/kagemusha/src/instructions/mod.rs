@@ -1,13 +1,62 @@
+pub mod initialize_protocol;
+pub mod update_protocol;
+pub mod set_protocol_admin;
 pub mod initialize;
+pub mod add_vault_account;
 pub mod deposit;
 pub mod deposit_sol;
 pub mod rebalance;
 pub mod withdraw;
 pub mod withdraw_sol;
+pub mod set_private;
+pub mod add_depositor;
+pub mod remove_depositor;
+pub mod set_strategy_fees;
+pub mod claim_curator_fees;
+pub mod claim_protocol_fees;
+pub mod register_referrer;
+pub mod claim_referrer_fees;
+pub mod propose_owner;
+pub mod accept_owner;
+pub mod cancel_owner_transfer;
+pub mod set_rebalancer;
+pub mod set_delegation;
+pub mod revoke_delegation;
+pub mod set_strategy_status;
+pub mod close_strategy;
+pub mod propose_weights;
+pub mod execute_weights;
+pub mod cancel_weights;
+pub mod set_weights_timelock;
+pub mod announce_rebalance;
+pub mod cancel_rebalance;
+pub mod guardian_freeze;
+pub mod unfreeze_strategy;
+pub mod guardian_revoke_rebalancer;
+pub mod guardian_revoke_delegation;
+pub mod set_mint_registry;
+pub mod remove_mint_registry;
+pub mod set_attestor;
+pub mod set_governance;
+pub mod create_proposal;
+pub mod cast_vote;
+pub mod execute_proposal;
+pub mod cancel_proposal;
+pub mod reclaim_vote;
+pub mod execute_fees;
+pub mod cancel_fees;
 
+#[allow(ambiguous_glob_reexports)]
+pub use initialize_protocol::*;
+#[allow(ambiguous_glob_reexports)]
+pub use update_protocol::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_protocol_admin::*;
 #[allow(ambiguous_glob_reexports)]
 pub use initialize::*;
 #[allow(ambiguous_glob_reexports)]
+pub use add_vault_account::*;
+#[allow(ambiguous_glob_reexports)]
 pub use deposit::*;
 #[allow(ambiguous_glob_reexports)]
 pub use deposit_sol::*;
@@ -17,3 +66,77 @@ pub use rebalance::*;
 pub use withdraw::*;
 #[allow(ambiguous_glob_reexports)]
 pub use withdraw_sol::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_private::*;
+#[allow(ambiguous_glob_reexports)]
+pub use add_depositor::*;
+#[allow(ambiguous_glob_reexports)]
+pub use remove_depositor::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_strategy_fees::*;
+#[allow(ambiguous_glob_reexports)]
+pub use claim_curator_fees::*;
+#[allow(ambiguous_glob_reexports)]
+pub use claim_protocol_fees::*;
+#[allow(ambiguous_glob_reexports)]
+pub use register_referrer::*;
+#[allow(ambiguous_glob_reexports)]
+pub use claim_referrer_fees::*;
+#[allow(ambiguous_glob_reexports)]
+pub use propose_owner::*;
+#[allow(ambiguous_glob_reexports)]
+pub use accept_owner::*;
+#[allow(ambiguous_glob_reexports)]
+pub use cancel_owner_transfer::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_rebalancer::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_delegation::*;
+#[allow(ambiguous_glob_reexports)]
+pub use revoke_delegation::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_strategy_status::*;
+#[allow(ambiguous_glob_reexports)]
+pub use close_strategy::*;
+#[allow(ambiguous_glob_reexports)]
+pub use propose_weights::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_weights::*;
+#[allow(ambiguous_glob_reexports)]
+pub use cancel_weights::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_weights_timelock::*;
+#[allow(ambiguous_glob_reexports)]
+pub use announce_rebalance::*;
+#[allow(ambiguous_glob_reexports)]
+pub use cancel_rebalance::*;
+#[allow(ambiguous_glob_reexports)]
+pub use guardian_freeze::*;
+#[allow(ambiguous_glob_reexports)]
+pub use unfreeze_strategy::*;
+#[allow(ambiguous_glob_reexports)]
+pub use guardian_revoke_rebalancer::*;
+#[allow(ambiguous_glob_reexports)]
+pub use guardian_revoke_delegation::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_mint_registry::*;
+#[allow(ambiguous_glob_reexports)]
+pub use remove_mint_registry::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_attestor::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_governance::*;
+#[allow(ambiguous_glob_reexports)]
+pub use create_proposal::*;
+#[allow(ambiguous_glob_reexports)]
+pub use cast_vote::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_proposal::*;
+#[allow(ambiguous_glob_reexports)]
+pub use cancel_proposal::*;
+#[allow(ambiguous_glob_reexports)]
+pub use reclaim_vote::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_fees::*;
+#[allow(ambiguous_glob_reexports)]
+pub use cancel_fees::*;
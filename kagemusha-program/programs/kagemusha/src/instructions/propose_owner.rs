@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::OwnershipTransferProposed;
+
+/// Propose a new owner for the strategy. Control only moves once the
+/// proposed owner accepts with `accept_owner`.
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+    require!(
+        new_owner != Pubkey::default() && new_owner != ctx.accounts.owner.key(),
+        KagemushaError::InvalidNewOwner
+    );
+
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.pending_owner = new_owner;
+
+    emit!(OwnershipTransferProposed {
+        strategy: strategy.key(),
+        owner: strategy.owner,
+        pending_owner: new_owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} ownership offered to {}", strategy.name_as_str(), new_owner);
+
+    Ok(())
+}
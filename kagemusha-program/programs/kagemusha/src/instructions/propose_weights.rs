@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::{MintRegistry, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::WeightsProposed;
+
+/// Announce a new composition (weights, mints and price feeds). It can be
+/// executed once the strategy's `weights_timelock` has elapsed, giving
+/// depositors time to exit. Replaces any composition already pending.
+/// Governed strategies change their composition through a proposal instead.
+///
+/// Remaining accounts: the MintRegistry entry of each proposed mint, in the
+/// order of `mints`.
+#[derive(Accounts)]
+pub struct ProposeWeights<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<ProposeWeights>,
+    target_weights: Vec<u16>,
+    mints: Vec<Pubkey>,
+    price_feeds: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.strategy.governance_enabled(),
+        KagemushaError::GovernanceRequired
+    );
+    StrategyVault::validate_composition(&target_weights, &mints, &price_feeds)?;
+    MintRegistry::verify_composition(ctx.remaining_accounts, &mints, &price_feeds)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let strategy = &mut ctx.accounts.strategy;
+    let eta = strategy.stage_weights(&target_weights, &mints, &price_feeds, false, now)?;
+
+    emit!(WeightsProposed {
+        strategy: strategy.key(),
+        num_tokens: strategy.pending_num_tokens,
+        target_weights: strategy.pending_target_weights,
+        mints: strategy.pending_mints,
+        eta,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Strategy {} composition change proposed, executable at {}", strategy.name_as_str(), eta);
+
+    Ok(())
+}
@@ -1,16 +1,11 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use anchor_lang::solana_program::program::{invoke_signed};
 use anchor_lang::solana_program::instruction::AccountMeta;
-use std::str::FromStr;
-use crate::state::StrategyVault;
+use crate::state::{Delegation, MintRegistry, ProtocolConfig, StrategyVault};
 use crate::errors::KagemushaError;
-
-pub const PROTOCOL_FEE_BPS: u64 = 50;
-
-pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
-
-pub const JUPITER_EVENT_AUTHORITY: Pubkey = pubkey!("D8cy77BBepLMngZx6ZukaTff5hCt1HrWyKk3Hnd9oitf");
+use crate::nav;
+use crate::oracle;
 
 pub const JUPITER_ROUTE_DISCRIMINATOR: [u8; 8] = [229, 23, 203, 151, 122, 227, 173, 42];
 
@@ -25,80 +20,141 @@ pub struct JupiterRouteInstruction {
     pub platform_fee_bps: u8,
 }
 
+/// Swap between two basket tokens through Jupiter.
+///
+/// Remaining accounts: (vault token account, mint, price feed) for each
+/// composition token. The basket is valued before and after the swap; a swap
+/// that moves it further from the target weights must have been announced
+/// with `announce_rebalance`.
 #[derive(Accounts)]
 pub struct Rebalance<'info> {
     #[account(
         mut,
-        has_one = owner @ KagemushaError::Unauthorized
+        has_one = share_mint @ KagemushaError::MintMismatch
     )]
     pub strategy: Account<'info, StrategyVault>,
 
-    pub owner: Signer<'info>,
+    pub share_mint: Account<'info, Mint>,
+
+    /// Strategy owner, its delegated rebalancer, or a session key with a Delegation
+    pub authority: Signer<'info>,
+
+    /// Required when `authority` signs through a session delegation
+    #[account(
+        mut,
+        seeds = [b"delegation", strategy.key().as_ref(), authority.key().as_ref()],
+        bump = delegation.bump
+    )]
+    pub delegation: Option<Account<'info, Delegation>>,
+
+    /// CHECK: Price feed of mint_in, validated against the strategy's feed and
+    /// only read when `authority` signs through a session delegation
+    pub price_feed_in: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
 
     /// CHECK: Validated via address constraint below - prevents arbitrary CPI
-    #[account(address = JUPITER_PROGRAM_ID)]
+    #[account(address = config.jupiter_program @ KagemushaError::InvalidJupiterProgram)]
     pub jupiter_program: UncheckedAccount<'info>,
 
-    /// CHECK: Fixed address constraint - safe for Jupiter event authority
-    #[account(address = JUPITER_EVENT_AUTHORITY)]
+    /// CHECK: Event authority PDA of the configured Jupiter program
+    #[account(address = config.jupiter_event_authority() @ KagemushaError::InvalidJupiterProgram)]
     pub jupiter_event_authority: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        constraint = vault_token_in.mint == mint_in.key() @ KagemushaError::MintMismatch
+        constraint = vault_token_in.mint == mint_in.key() @ KagemushaError::MintMismatch,
+        constraint = strategy.vault_account_for(&mint_in.key()) == Some(vault_token_in.key()) @ KagemushaError::InvalidVaultAccount
     )]
     pub vault_token_in: Account<'info, TokenAccount>,
 
     #[account(
         mut,
-        constraint = vault_token_out.mint == mint_out.key() @ KagemushaError::MintMismatch
+        constraint = vault_token_out.mint == mint_out.key() @ KagemushaError::MintMismatch,
+        constraint = strategy.vault_account_for(&mint_out.key()) == Some(vault_token_out.key()) @ KagemushaError::InvalidVaultAccount
     )]
     pub vault_token_out: Account<'info, TokenAccount>,
 
-    #[account(
-        mut,
-        constraint = protocol_fee_account.key() == Pubkey::from_str(PROTOCOL_TREASURY_PUBKEY).unwrap() @ KagemushaError::InvalidFeeAccount
-    )]
-    pub protocol_fee_account: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-
     pub token_program: Program<'info, Token>,
 
     pub mint_in: Account<'info, anchor_spl::token::Mint>,
     pub mint_out: Account<'info, anchor_spl::token::Mint>,
-}
 
-pub const PROTOCOL_TREASURY_PUBKEY: &str = "9WzDXwBbmkg8ZTbNMmuUwqPFthNQ4EqC";
+    /// Protocol approval of the output mint
+    #[account(
+        seeds = [b"mint_registry", mint_out.key().as_ref()],
+        bump = mint_out_registry.bump
+    )]
+    pub mint_out_registry: Account<'info, MintRegistry>,
+}
 
-pub fn handler(
-    ctx: Context<Rebalance>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Rebalance<'info>>,
     amount_in: u64,
     minimum_amount_out: u64,
     route_data: Vec<u8>,
 ) -> Result<()> {
     let strategy = &mut ctx.accounts.strategy;
+    let config = &ctx.accounts.config;
+    let now = Clock::get()?.unix_timestamp;
 
-    require!(strategy.is_active, KagemushaError::StrategyInactive);
+    require!(!config.rebalances_paused, KagemushaError::ProtocolPaused);
+    require!(strategy.status.allows_rebalance(), KagemushaError::StrategyInactive);
     require!(amount_in > 0, KagemushaError::InsufficientFunds);
     require!(minimum_amount_out > 0, KagemushaError::InsufficientFunds);
-
     require!(
-        ctx.accounts.vault_token_in.amount >= amount_in,
-        KagemushaError::InsufficientFunds
+        ctx.accounts.mint_in.key() != ctx.accounts.mint_out.key(),
+        KagemushaError::DuplicateMint
     );
 
+    // Fees reserved in the input account are not available to swap
+    let index_in = strategy
+        .basket_index(&ctx.accounts.mint_in.key())
+        .ok_or(KagemushaError::MintMismatch)?;
+    let balance_in_before = ctx.accounts.vault_token_in.amount;
+    let balance_out_before = ctx.accounts.vault_token_out.amount;
+    let available_in = balance_in_before.saturating_sub(strategy.reserved_fees(index_in)?);
+    require!(available_in >= amount_in, KagemushaError::InsufficientFunds);
+
+    // Session keys are bounded by their delegation's limits, valued in USD.
+    // The price is read now and the limits are debited once the swap's
+    // actual input is known.
+    let delegated_price_in = if strategy.can_rebalance(&ctx.accounts.authority.key()) {
+        None
+    } else {
+        require!(ctx.accounts.delegation.is_some(), KagemushaError::Unauthorized);
+        let price_feed = ctx
+            .accounts
+            .price_feed_in
+            .as_ref()
+            .ok_or(KagemushaError::InvalidPriceFeed)?;
+        require!(
+            price_feed.key() == strategy.price_feeds[index_in],
+            KagemushaError::InvalidPriceFeed
+        );
+        Some(oracle::load_price(price_feed, &config.pyth_program, now)?)
+    };
+
+    let drift_before = nav::target_drift(strategy, &nav::load_basket(strategy, config, ctx.remaining_accounts)?)?;
+
     require!(!route_data.is_empty(), KagemushaError::InvalidRouteData);
     require!(route_data.len() <= 1200, KagemushaError::InvalidRouteData);
 
     let calculated_fee = amount_in
-        .checked_mul(PROTOCOL_FEE_BPS)
+        .checked_mul(config.protocol_fee_bps as u64)
         .ok_or(KagemushaError::MathOverflow)?
         .checked_div(10000)
         .ok_or(KagemushaError::MathOverflow)?;
 
-    let fee = std::cmp::max(1, calculated_fee);
+    let fee = if config.protocol_fee_bps == 0 {
+        0
+    } else {
+        std::cmp::max(1, calculated_fee)
+    };
 
     require!(
         amount_in > fee,
@@ -109,56 +165,43 @@ pub fn handler(
         .checked_sub(fee)
         .ok_or(KagemushaError::MathOverflow)?;
 
-    strategy.fees_collected = strategy
-        .fees_collected
-        .checked_add(fee)
-        .ok_or(KagemushaError::MathOverflow)?;
-    strategy.last_rebalance = Clock::get()?.unix_timestamp;
+    // The fee stays in the vault, reserved for the treasury and curator until claimed
+    let fee_split = strategy.reserve_swap_fee(index_in, fee, config)?;
+    strategy.accrue_management_fee(ctx.accounts.share_mint.supply, now, config)?;
+    strategy.last_rebalance = now;
 
     msg!("Kagemusha Rebalance:");
     msg!("  Strategy: {}", strategy.name_as_str());
     msg!("  Amount In: {}", amount_in);
-    msg!("  Protocol Fee (0.5%): {}", fee);
+    msg!("  Protocol Fee ({} bps): {}", config.protocol_fee_bps, fee);
+    msg!("  Fee Split: protocol {}, curator {}", fee_split.protocol, fee_split.curator);
     msg!("  Swap Amount: {}", swap_amount);
     msg!("  Min Amount Out: {}", minimum_amount_out);
 
     // Build PDA signer seeds for strategy vault
     let seeds: &[&[u8]] = &[
         b"strategy",
-        strategy.owner.as_ref(),
-        strategy.name.as_ref(),
+        strategy.creator.as_ref(),
+        strategy.name_seed(),
         &[strategy.bump]
     ];
     let signer_seeds = &[seeds];
 
-    let fee_transfer = anchor_spl::token::Transfer {
-        from: ctx.accounts.vault_token_in.to_account_info(),
-        to: ctx.accounts.protocol_fee_account.to_account_info(),
-        authority: strategy.to_account_info(),
-    };
-    anchor_spl::token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            fee_transfer,
-            signer_seeds,
-        ),
-        fee,
-    )?;
-
-    let mut jupiter_accounts = vec![
-        AccountMeta::new_readonly(ctx.accounts.jupiter_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.jupiter_event_authority.key(), false),
-        AccountMeta::new(ctx.accounts.vault_token_in.key(), true),
-        AccountMeta::new(ctx.accounts.vault_token_out.key(), true),
-        AccountMeta::new(ctx.accounts.owner.key(), true),
-        AccountMeta::new(ctx.accounts.user_token_account.key(), false),
+    // The strategy PDA is the token authority of both vault accounts and
+    // signs the swap through the seeds above; nothing else signs
+    let jupiter_accounts = vec![
+        AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
+        AccountMeta::new_readonly(strategy.key(), true),
+        AccountMeta::new(ctx.accounts.vault_token_in.key(), false),
+        AccountMeta::new(ctx.accounts.vault_token_out.key(), false),
         AccountMeta::new_readonly(ctx.accounts.mint_in.key(), false),
         AccountMeta::new_readonly(ctx.accounts.mint_out.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.jupiter_event_authority.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.jupiter_program.key(), false),
     ];
 
     let jupiter_instruction = anchor_lang::solana_program::instruction::Instruction {
-        program_id: JUPITER_PROGRAM_ID,
+        program_id: ctx.accounts.jupiter_program.key(),
         accounts: jupiter_accounts,
         data: route_data,
     };
@@ -166,13 +209,14 @@ pub fn handler(
     invoke_signed(
         &jupiter_instruction,
         &[
-            ctx.accounts.jupiter_program.to_account_info(),
-            ctx.accounts.jupiter_event_authority.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            strategy.to_account_info(),
             ctx.accounts.vault_token_in.to_account_info(),
             ctx.accounts.vault_token_out.to_account_info(),
-            ctx.accounts.owner.to_account_info(),
-            ctx.accounts.user_token_account.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mint_in.to_account_info(),
+            ctx.accounts.mint_out.to_account_info(),
+            ctx.accounts.jupiter_event_authority.to_account_info(),
+            ctx.accounts.jupiter_program.to_account_info(),
         ],
         signer_seeds,
     ).map_err(|e| {
@@ -180,9 +224,47 @@ pub fn handler(
         KagemushaError::JupiterSwapFailed
     })?;
 
+    // The route data is opaque, so check what the swap actually moved: no
+    // more than the swap amount in, at least the minimum out, and the
+    // reserved fees still in place
+    ctx.accounts.vault_token_in.reload()?;
+    ctx.accounts.vault_token_out.reload()?;
+    let spent_in = balance_in_before
+        .checked_sub(ctx.accounts.vault_token_in.amount)
+        .ok_or(KagemushaError::SwapResultMismatch)?;
+    let received_out = ctx
+        .accounts
+        .vault_token_out
+        .amount
+        .checked_sub(balance_out_before)
+        .ok_or(KagemushaError::SwapResultMismatch)?;
+    require!(spent_in <= swap_amount, KagemushaError::SwapResultMismatch);
+    require!(received_out >= minimum_amount_out, KagemushaError::SwapResultMismatch);
+    require!(
+        ctx.accounts.vault_token_in.amount >= strategy.reserved_fees(index_in)?,
+        KagemushaError::SwapResultMismatch
+    );
+
+    if let (Some(price_in), Some(delegation)) = (delegated_price_in, ctx.accounts.delegation.as_mut()) {
+        let notional = price_in.usd_value(spent_in, ctx.accounts.mint_in.decimals)?;
+        delegation.debit(notional, &ctx.accounts.mint_out.key(), now)?;
+    }
+
+    // Swaps away from the published target only run once announced and due
+    let drift_after = nav::target_drift(strategy, &nav::load_basket(strategy, config, ctx.remaining_accounts)?)?;
+    if drift_after > drift_before {
+        strategy.take_announced_rebalance(
+            &ctx.accounts.mint_in.key(),
+            &ctx.accounts.mint_out.key(),
+            spent_in,
+            now,
+        )?;
+        msg!("  Off-target rebalance executed as announced");
+    }
+
     msg!("Kagemusha: Rebalance complete. Jupiter swap executed successfully.");
     msg!("  Fee collected: {}", fee);
-    msg!("  Swap executed: {}", swap_amount);
+    msg!("  Swap executed: {} in, {} out", spent_in, received_out);
 
     Ok(())
 }
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use crate::state::{Proposal, ProposalStatus, VoteRecord};
+use crate::errors::KagemushaError;
+
+/// Return the share tokens locked by a vote once voting has ended or the
+/// proposal was cancelled, and close the vote record and its escrow. The
+/// votes already counted stay on the proposal.
+#[derive(Accounts)]
+pub struct ReclaimVote<'info> {
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote_record.bump,
+        has_one = proposal @ KagemushaError::Unauthorized,
+        has_one = voter @ KagemushaError::Unauthorized,
+        close = voter
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"vote_escrow", vote_record.key().as_ref()],
+        bump,
+        token::authority = vote_record,
+    )]
+    pub vote_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = vote_escrow.mint,
+        token::authority = voter,
+    )]
+    pub voter_share_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<ReclaimVote>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &ctx.accounts.proposal;
+    require!(
+        proposal.status != ProposalStatus::Voting || now >= proposal.voting_ends_at,
+        KagemushaError::VotingNotEnded
+    );
+
+    let amount = ctx.accounts.vote_escrow.amount;
+    let proposal_key = proposal.key();
+    let voter_key = ctx.accounts.voter.key();
+    let seeds: &[&[u8]] = &[
+        b"vote",
+        proposal_key.as_ref(),
+        voter_key.as_ref(),
+        &[ctx.accounts.vote_record.bump]
+    ];
+    let signer_seeds = &[seeds];
+
+    let transfer_accounts = Transfer {
+        from: ctx.accounts.vote_escrow.to_account_info(),
+        to: ctx.accounts.voter_share_account.to_account_info(),
+        authority: ctx.accounts.vote_record.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let close_accounts = CloseAccount {
+        account: ctx.accounts.vote_escrow.to_account_info(),
+        destination: ctx.accounts.voter.to_account_info(),
+        authority: ctx.accounts.vote_record.to_account_info(),
+    };
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        close_accounts,
+        signer_seeds,
+    ))?;
+
+    msg!("Kagemusha: {} reclaimed {} shares from proposal {}", voter_key, amount, proposal.id);
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::{ReferrerAccount, StrategyVault};
+
+/// Open the signer's ReferrerAccount in a strategy, so depositors can name
+/// them as referrer.
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        init,
+        payer = referrer,
+        space = ReferrerAccount::LEN,
+        seeds = [b"referrer", strategy.key().as_ref(), referrer.key().as_ref()],
+        bump
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let account = &mut ctx.accounts.referrer_account;
+    account.strategy = ctx.accounts.strategy.key();
+    account.referrer = ctx.accounts.referrer.key();
+    account.pending_shares = 0;
+    account.total_earned = 0;
+    account.bump = ctx.bumps.referrer_account;
+
+    msg!("Kagemusha: Referrer {} registered in strategy {}", account.referrer, ctx.accounts.strategy.name_as_str());
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::{DepositorPass, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::DepositorRemoved;
+
+/// Revoke a DepositorPass. Signed by the strategy owner or its attestor; the
+/// pass rent goes back to whoever issued it. Existing positions can still
+/// withdraw.
+#[derive(Accounts)]
+pub struct RemoveDepositor<'info> {
+    #[account(constraint = strategy.can_attest(&authority.key()) @ KagemushaError::Unauthorized)]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        mut,
+        seeds = [b"depositor_pass", strategy.key().as_ref(), depositor_pass.depositor.as_ref()],
+        bump = depositor_pass.bump,
+        has_one = issued_by @ KagemushaError::Unauthorized,
+        close = issued_by
+    )]
+    pub depositor_pass: Account<'info, DepositorPass>,
+
+    /// CHECK: Issuer recorded on the pass, receives its rent
+    #[account(mut)]
+    pub issued_by: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<RemoveDepositor>) -> Result<()> {
+    emit!(DepositorRemoved {
+        strategy: ctx.accounts.strategy.key(),
+        depositor: ctx.accounts.depositor_pass.depositor,
+        revoked_by: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Kagemusha: Depositor {} removed from strategy {}",
+        ctx.accounts.depositor_pass.depositor,
+        ctx.accounts.strategy.name_as_str()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{MintRegistry, ProtocolConfig};
+use crate::errors::KagemushaError;
+use crate::events::MintRemoved;
+
+/// Withdraw a mint's approval. Strategies already holding it are unaffected,
+/// but it can no longer be added to a basket or bought in a rebalance.
+#[derive(Accounts)]
+pub struct RemoveMintRegistry<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_registry", mint_registry.mint.as_ref()],
+        bump = mint_registry.bump,
+        close = admin
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = admin @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<RemoveMintRegistry>) -> Result<()> {
+    emit!(MintRemoved {
+        mint: ctx.accounts.mint_registry.mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Mint {} removed from registry", ctx.accounts.mint_registry.mint);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::{Delegation, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::DelegationRevoked;
+
+/// Revoke a rebalance delegation, returning its rent to the owner.
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    #[account(has_one = owner @ KagemushaError::Unauthorized)]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        mut,
+        seeds = [b"delegation", strategy.key().as_ref(), delegation.delegate.as_ref()],
+        bump = delegation.bump,
+        close = owner
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<RevokeDelegation>) -> Result<()> {
+    emit!(DelegationRevoked {
+        strategy: ctx.accounts.strategy.key(),
+        delegate: ctx.accounts.delegation.delegate,
+        revoked_by: ctx.accounts.owner.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Kagemusha: Delegate {} removed from strategy {}",
+        ctx.accounts.delegation.delegate,
+        ctx.accounts.strategy.name_as_str()
+    );
+
+    Ok(())
+}
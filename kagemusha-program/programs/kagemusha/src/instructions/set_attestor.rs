@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::AttestorChanged;
+
+/// Let another key, such as a KYC provider, issue and revoke DepositorPasses
+/// for a private strategy. Pass the default pubkey to remove it. Passes it
+/// already issued stay valid until revoked.
+#[derive(Accounts)]
+pub struct SetAttestor<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetAttestor>, attestor: Pubkey) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    let previous_attestor = strategy.attestor;
+    strategy.attestor = attestor;
+
+    emit!(AttestorChanged {
+        strategy: strategy.key(),
+        previous_attestor,
+        attestor,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} attestor set to {}", strategy.name_as_str(), attestor);
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::{Delegation, DelegationParams, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::DelegationSet;
+
+/// Create or update a scoped rebalance session for `delegate`.
+/// Today's counted notional is kept when the limits are updated.
+/// Governed strategies can't grant or widen delegations; like a new
+/// rebalancer, that needs a passed proposal. `revoke_delegation` still works.
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct SetDelegation<'info> {
+    #[account(
+        has_one = owner @ KagemushaError::Unauthorized,
+        constraint = !strategy.frozen @ KagemushaError::StrategyFrozen
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Delegation::LEN,
+        seeds = [b"delegation", strategy.key().as_ref(), delegate.as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetDelegation>, delegate: Pubkey, params: DelegationParams) -> Result<()> {
+    require!(
+        !ctx.accounts.strategy.governance_enabled(),
+        KagemushaError::GovernanceRequired
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(params.expires_at > now, KagemushaError::DelegationExpired);
+
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.strategy = ctx.accounts.strategy.key();
+    delegation.delegate = delegate;
+    delegation.bump = ctx.bumps.delegation;
+    delegation.apply(&params)?;
+
+    emit!(DelegationSet {
+        strategy: delegation.strategy,
+        delegate,
+        expires_at: delegation.expires_at,
+        max_notional_per_rebalance: delegation.max_notional_per_rebalance,
+        max_daily_notional: delegation.max_daily_notional,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Delegate {} may rebalance strategy {} until {}", delegate, ctx.accounts.strategy.name_as_str(), delegation.expires_at);
+
+    Ok(())
+}
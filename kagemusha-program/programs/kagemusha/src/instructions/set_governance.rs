@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::GovernanceEnabled;
+use crate::fees;
+
+/// Put the strategy under shareholder governance. From then on, weight
+/// updates, fee increases and new rebalancers need a passed proposal.
+/// Governance can't be disabled or reconfigured afterwards.
+#[derive(Accounts)]
+pub struct SetGovernance<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<SetGovernance>,
+    quorum_bps: u16,
+    threshold_bps: u16,
+    voting_period: i64,
+) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    require!(!strategy.governance_enabled(), KagemushaError::GovernanceAlreadyEnabled);
+    require!(
+        quorum_bps > 0 && quorum_bps as u64 <= fees::BPS_DENOMINATOR,
+        KagemushaError::InvalidGovernanceParams
+    );
+    // Passing needs more than `threshold_bps` of the votes, so at least a majority
+    require!(
+        threshold_bps as u64 >= fees::BPS_DENOMINATOR / 2
+            && (threshold_bps as u64) < fees::BPS_DENOMINATOR,
+        KagemushaError::InvalidGovernanceParams
+    );
+    require!(
+        voting_period >= StrategyVault::MIN_VOTING_PERIOD,
+        KagemushaError::InvalidGovernanceParams
+    );
+
+    strategy.governance_quorum_bps = quorum_bps;
+    strategy.governance_threshold_bps = threshold_bps;
+    strategy.governance_voting_period = voting_period;
+
+    emit!(GovernanceEnabled {
+        strategy: strategy.key(),
+        quorum_bps,
+        threshold_bps,
+        voting_period,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Kagemusha: Strategy {} governed (quorum {} bps, threshold {} bps, voting {}s)",
+        strategy.name_as_str(),
+        quorum_bps,
+        threshold_bps,
+        voting_period
+    );
+
+    Ok(())
+}
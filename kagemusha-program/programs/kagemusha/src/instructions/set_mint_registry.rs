@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+use crate::state::{MintRegistry, ProtocolConfig};
+use crate::errors::KagemushaError;
+use crate::events::MintApproved;
+
+/// Approve a mint for use in strategy baskets, or update its oracle and risk
+/// tier. Decimals are read from the mint itself. Vault token accounts are
+/// SPL Token accounts, so Token-2022 mints can't be approved.
+#[derive(Accounts)]
+pub struct SetMintRegistry<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = MintRegistry::LEN,
+        seeds = [b"mint_registry", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Pyth price account recorded for the mint
+    pub oracle: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = admin @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetMintRegistry>, risk_tier: u8) -> Result<()> {
+    require!(
+        ctx.accounts.oracle.owner == &ctx.accounts.config.pyth_program,
+        KagemushaError::InvalidPriceFeed
+    );
+
+    let entry = &mut ctx.accounts.mint_registry;
+    entry.mint = ctx.accounts.mint.key();
+    entry.oracle = ctx.accounts.oracle.key();
+    entry.decimals = ctx.accounts.mint.decimals;
+    entry.token_program = ctx.accounts.token_program.key();
+    entry.risk_tier = risk_tier;
+    entry.bump = ctx.bumps.mint_registry;
+
+    emit!(MintApproved {
+        mint: entry.mint,
+        oracle: entry.oracle,
+        risk_tier,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Mint {} approved (tier {}, oracle {})", entry.mint, risk_tier, entry.oracle);
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+
+/// Toggle whether deposits require a DepositorPass.
+#[derive(Accounts)]
+pub struct SetPrivate<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetPrivate>, is_private: bool) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.is_private = is_private;
+
+    msg!("Kagemusha: Strategy {} private = {}", strategy.name_as_str(), is_private);
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::ProtocolConfig;
+use crate::errors::KagemushaError;
+
+/// Hand the ProtocolConfig admin role to a new key.
+#[derive(Accounts)]
+pub struct SetProtocolAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = admin @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetProtocolAdmin>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(new_admin != config.guardian, KagemushaError::InvalidGuardian);
+    config.admin = new_admin;
+
+    msg!("Kagemusha: Protocol admin set to {}", new_admin);
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+use crate::events::RebalancerChanged;
+
+/// Delegate rebalancing to another key, such as an AI agent or keeper.
+/// The rebalancer can only sign rebalance instructions; pass the default
+/// pubkey to remove it. Governed strategies can only remove it here; a new
+/// rebalancer needs a passed proposal.
+#[derive(Accounts)]
+pub struct SetRebalancer<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        constraint = !strategy.frozen @ KagemushaError::StrategyFrozen
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetRebalancer>, rebalancer: Pubkey) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    require!(
+        !strategy.governance_enabled() || rebalancer == Pubkey::default(),
+        KagemushaError::GovernanceRequired
+    );
+    let previous_rebalancer = strategy.rebalancer;
+    strategy.rebalancer = rebalancer;
+
+    emit!(RebalancerChanged {
+        strategy: strategy.key(),
+        previous_rebalancer,
+        rebalancer,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} rebalancer set to {}", strategy.name_as_str(), rebalancer);
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::{ProtocolConfig, StrategyFeeParams, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::FeesProposed;
+
+/// Change the strategy's fee rates.
+/// Rates that don't raise any fee apply at once: management fees owed at the
+/// old rate are accrued first, and performance fees keep crystallising
+/// against the existing high-water mark. Rates that raise a fee are staged
+/// like a composition change and only apply through `execute_fees` once the
+/// strategy's `weights_timelock` has elapsed, so depositors can exit without
+/// exit fees first. Governed strategies can lower fees directly but raise
+/// them only through a proposal.
+#[derive(Accounts)]
+pub struct SetStrategyFees<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        has_one = share_mint @ KagemushaError::MintMismatch,
+        constraint = !strategy.frozen @ KagemushaError::StrategyFrozen
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub share_mint: Account<'info, Mint>,
+}
+
+pub fn handler(ctx: Context<SetStrategyFees>, params: StrategyFeeParams) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let strategy = &mut ctx.accounts.strategy;
+    let raises_fees = strategy.raises_fees(&params);
+    require!(
+        !strategy.governance_enabled() || !raises_fees,
+        KagemushaError::GovernanceRequired
+    );
+
+    if raises_fees {
+        let eta = strategy.stage_fees(&params, false, now, &ctx.accounts.config)?;
+
+        emit!(FeesProposed {
+            strategy: strategy.key(),
+            fees: params,
+            eta,
+            timestamp: now,
+        });
+
+        msg!("Kagemusha: Strategy {} fee increase proposed, executable at {}", strategy.name_as_str(), eta);
+        return Ok(());
+    }
+
+    strategy.accrue_management_fee(ctx.accounts.share_mint.supply, now, &ctx.accounts.config)?;
+    strategy.apply_fees(&params, &ctx.accounts.config)?;
+
+    msg!("Kagemusha: Strategy {} fees updated", strategy.name_as_str());
+    msg!("  Management Fee: {} bps/year", strategy.management_fee_bps);
+    msg!("  Performance Fee: {} bps every {}s", strategy.performance_fee_bps, strategy.performance_fee_interval);
+    msg!("  Entry/Exit Fee: {}/{} bps", strategy.entry_fee_bps, strategy.exit_fee_bps);
+    msg!("  Referrer Share: {} bps", strategy.referrer_fee_share_bps);
+
+    Ok(())
+}
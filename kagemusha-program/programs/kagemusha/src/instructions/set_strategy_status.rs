@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{StrategyStatus, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::StrategyStatusChanged;
+
+/// Move a strategy between Active, DepositsPaused, WithdrawOnly and Shutdown.
+/// Shutdown is final, and a strategy frozen by the guardian can only be
+/// released by the protocol admin with `unfreeze_strategy`.
+#[derive(Accounts)]
+pub struct SetStrategyStatus<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized,
+        constraint = !strategy.frozen @ KagemushaError::StrategyFrozen
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetStrategyStatus>, status: StrategyStatus) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    let previous_status = strategy.status;
+    require!(previous_status != StrategyStatus::Shutdown, KagemushaError::StrategyShutdown);
+    strategy.status = status;
+
+    emit!(StrategyStatusChanged {
+        strategy: strategy.key(),
+        previous_status,
+        status,
+        authority: ctx.accounts.owner.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} status {:?} -> {:?}", strategy.name_as_str(), previous_status, status);
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::StrategyVault;
+use crate::errors::KagemushaError;
+
+/// Change the delay between proposing and executing a composition change.
+/// A change already pending keeps its execution time.
+#[derive(Accounts)]
+pub struct SetWeightsTimelock<'info> {
+    #[account(
+        mut,
+        has_one = owner @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetWeightsTimelock>, timelock: i64) -> Result<()> {
+    require!(
+        timelock >= StrategyVault::MIN_WEIGHTS_TIMELOCK,
+        KagemushaError::TimelockTooShort
+    );
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.weights_timelock = timelock;
+
+    msg!("Kagemusha: Strategy {} weights timelock set to {}s", strategy.name_as_str(), timelock);
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, StrategyStatus, StrategyVault};
+use crate::errors::KagemushaError;
+use crate::events::StrategyUnfrozen;
+
+/// Lift a guardian freeze and set the strategy's status.
+#[derive(Accounts)]
+pub struct UnfreezeStrategy<'info> {
+    #[account(
+        mut,
+        constraint = strategy.frozen @ KagemushaError::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyVault>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = admin @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UnfreezeStrategy>, status: StrategyStatus) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    require!(strategy.status != StrategyStatus::Shutdown, KagemushaError::StrategyShutdown);
+    strategy.frozen = false;
+    strategy.status = status;
+
+    emit!(StrategyUnfrozen {
+        strategy: strategy.key(),
+        admin: ctx.accounts.admin.key(),
+        status,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Kagemusha: Strategy {} unfrozen with status {:?}", strategy.name_as_str(), status);
+
+    Ok(())
+}
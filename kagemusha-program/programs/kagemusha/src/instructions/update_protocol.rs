@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, ProtocolConfigParams};
+use crate::errors::KagemushaError;
+
+/// Update treasury, guardian, fee rates, Jupiter program and pause flags.
+#[derive(Accounts)]
+pub struct UpdateProtocol<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = config.bump,
+        has_one = admin @ KagemushaError::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateProtocol>, params: ProtocolConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.apply(&params)?;
+
+    msg!("Kagemusha: Protocol config updated");
+    msg!("  Treasury: {}", config.treasury);
+    msg!("  Guardian: {}", config.guardian);
+    msg!("  Protocol Fee: {} bps", config.protocol_fee_bps);
+    msg!("  Protocol Fee Share: {} bps", config.protocol_fee_share_bps);
+    msg!("  Max Entry/Exit Fee: {} bps", config.max_entry_exit_fee_bps);
+    msg!("  Deposits Paused: {}", config.deposits_paused);
+    msg!("  Rebalances Paused: {}", config.rebalances_paused);
+
+    Ok(())
+}
@@ -1,85 +1,233 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer};
-use crate::state::{StrategyVault, UserPosition};
+use anchor_spl::token::{Burn, Mint, Token, TokenAccount, Transfer};
+use crate::state::{ProtocolConfig, ReferrerAccount, StrategyVault, UserPosition};
 use crate::errors::KagemushaError;
+use crate::events::Withdrawn;
+use crate::nav;
 
+/// Redeem shares for a pro-rata slice of every basket asset.
+/// Callable by any share holder for part or all of their shares.
+///
+/// Remaining accounts: (vault token account, mint, price feed) for each
+/// composition token, followed by the receiving token account for each of
+/// them in the same order.
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub strategy: Account<'info, StrategyVault>,
+
     #[account(
-        mut,
-        has_one = owner @ KagemushaError::Unauthorized
+        seeds = [b"protocol_config"],
+        bump = config.bump
     )]
-    pub strategy: Account<'info, StrategyVault>,
+    pub config: Account<'info, ProtocolConfig>,
 
+    /// The holder's deposit record, if they deposited. Shares received from
+    /// fee claims or by transfer can be redeemed without one.
     #[account(
         mut,
-        seeds = [b"position", strategy.key().as_ref(), owner.key().as_ref()],
-        bump,
-        constraint = position.user == owner.key() @ KagemushaError::Unauthorized,
-        close = owner
+        seeds = [b"position", strategy.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        constraint = position.user == user.key() @ KagemushaError::Unauthorized,
     )]
-    pub position: Account<'info, UserPosition>,
+    pub position: Option<Account<'info, UserPosition>>,
 
-    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
 
     #[account(
         mut,
-        constraint = vault_token_account.mint == strategy_mint.key() @ KagemushaError::MintMismatch,
-        constraint = vault_token_account.amount >= position.lp_shares @ KagemushaError::InsufficientFunds
+        seeds = [b"referrer", strategy.key().as_ref(), referrer_account.referrer.as_ref()],
+        bump = referrer_account.bump
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
 
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    /// CHECK: This is the vault's SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"vault_sol", strategy.key().as_ref()],
+        bump
+    )]
+    pub vault_sol: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        address = strategy.share_mint @ KagemushaError::MintMismatch
+    )]
+    pub share_mint: Account<'info, Mint>,
 
-    pub strategy_mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = user,
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<Withdraw>,
-    amount: u64,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
+    shares: u64,
 ) -> Result<()> {
-    let strategy = &mut ctx.accounts.strategy;
-    let position = &ctx.accounts.position;
+    // Accrue fees owed up to now before pricing shares
+    let now = Clock::get()?.unix_timestamp;
+    let minted_supply = ctx.accounts.share_mint.supply;
+    ctx.accounts.strategy.accrue_management_fee(minted_supply, now, &ctx.accounts.config)?;
+
+    let strategy = &ctx.accounts.strategy;
+
+    // Validate withdrawal amount against the shares the user holds.
+    // Withdrawals are open in every strategy status.
+    require!(shares > 0, KagemushaError::InsufficientFunds);
+    require!(
+        shares <= ctx.accounts.user_share_account.amount,
+        KagemushaError::InsufficientFunds
+    );
+
+    let num_tokens = strategy.num_tokens as usize;
+    let holdings = nav::load_holdings(strategy, ctx.remaining_accounts)?;
+    let receivers = &ctx.remaining_accounts[num_tokens * nav::ACCOUNTS_PER_ASSET..];
+    require!(receivers.len() >= num_tokens, KagemushaError::InvalidBasketAccounts);
 
-    // Validate strategy is active
-    require!(strategy.is_active, KagemushaError::StrategyInactive);
+    // The payout is in kind and needs no prices, so a stale or missing feed
+    // never blocks it. The performance fee is crystallised when the basket
+    // can be priced; otherwise it waits for the next priced instruction and
+    // the last recorded NAV stands in for the vault's value.
+    let sol_lamports = nav::liquid_lamports(&ctx.accounts.vault_sol)?;
+    let priced_nav = nav::load_basket(strategy, &ctx.accounts.config, ctx.remaining_accounts)
+        .and_then(|basket| nav::compute_nav(&basket, sol_lamports));
+    let nav_before = match priced_nav {
+        Ok(nav_before) => {
+            ctx.accounts.strategy.crystallize_performance_fee(
+                nav_before,
+                minted_supply,
+                now,
+                true,
+                &ctx.accounts.config,
+            )?;
+            nav_before
+        }
+        Err(_) => {
+            msg!("Kagemusha: Basket could not be priced, withdrawing without crystallising fees");
+            strategy.tvl
+        }
+    };
+    let total_shares = ctx.accounts.strategy.total_shares(minted_supply)?;
 
-    // Validate withdrawal amount
-    require!(amount > 0, KagemushaError::InsufficientFunds);
-    require!(amount <= position.lp_shares, KagemushaError::InsufficientFunds);
+    // Exit fee is taken in shares and split between the fee recipients.
+    // It is waived while an announced composition change is pending.
+    let referred = match ctx.accounts.position.as_deref() {
+        Some(position) => position.check_referrer(ctx.accounts.referrer_account.as_deref())?,
+        None => false,
+    };
+    let exit_fee_bps = ctx.accounts.strategy.effective_exit_fee_bps(now);
+    let (redeemed_shares, fee_split) = ctx.accounts.strategy.charge_share_fee(
+        shares,
+        exit_fee_bps,
+        &ctx.accounts.config,
+        referred,
+    )?;
+    if let Some(referrer_account) = ctx.accounts.referrer_account.as_mut() {
+        referrer_account.credit(fee_split.referrer)?;
+    }
+    let strategy = &ctx.accounts.strategy;
 
     // Build PDA signer seeds for strategy vault
     let seeds: &[&[u8]] = &[
         b"strategy",
-        strategy.owner.as_ref(),
-        strategy.name.as_ref(),
+        strategy.creator.as_ref(),
+        strategy.name_seed(),
         &[strategy.bump]
     ];
     let signer_seeds = &[seeds];
 
-    // Transfer tokens to user
-    let transfer_accounts = Transfer {
-        from: ctx.accounts.vault_token_account.to_account_info(),
-        to: ctx.accounts.user_token_account.to_account_info(),
-        authority: strategy.to_account_info(),
+    // Burn the redeemed share tokens
+    let burn_accounts = Burn {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        from: ctx.accounts.user_share_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
     };
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_accounts,
-        signer_seeds,
-    );
-    anchor_spl::token::transfer(transfer_ctx, amount)?;
+    anchor_spl::token::burn(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts),
+        shares,
+    )?;
+
+    // Transfer the pro-rata slice of each basket asset to the user
+    for (asset, receiver) in holdings.iter().zip(receivers.iter()) {
+        let payout = nav::pro_rata(asset.amount, redeemed_shares, total_shares)?;
+        if payout == 0 {
+            continue;
+        }
+
+        let receiver_account = TokenAccount::try_deserialize(&mut &receiver.data.borrow()[..])?;
+        require!(receiver_account.mint == asset.mint, KagemushaError::MintMismatch);
+
+        let transfer_accounts = Transfer {
+            from: asset.info.clone(),
+            to: receiver.clone(),
+            authority: strategy.to_account_info(),
+        };
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        anchor_spl::token::transfer(transfer_ctx, payout)?;
+    }
 
-    // Update strategy TVL
-    strategy.tvl = strategy
-        .tvl
-        .checked_sub(amount)
+    // Pay out the pro-rata slice of the vault's SOL
+    let sol_payout = nav::pro_rata(sol_lamports, redeemed_shares, total_shares)?;
+    if sol_payout > 0 {
+        let strategy_key = strategy.key();
+        let vault_sol_seeds: &[&[u8]] = &[
+            b"vault_sol",
+            strategy_key.as_ref(),
+            &[ctx.bumps.vault_sol]
+        ];
+        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+            ctx.accounts.vault_sol.key,
+            ctx.accounts.user.key,
+            sol_payout,
+        );
+        anchor_lang::solana_program::program::invoke_signed(
+            &transfer_ix,
+            &[
+                ctx.accounts.vault_sol.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            &[vault_sol_seeds],
+        )?;
+    }
+
+    // Update strategy TVL to the post-withdrawal NAV
+    let value = nav::pro_rata(nav_before, redeemed_shares, total_shares)?;
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.tvl = nav_before
+        .checked_sub(value)
         .ok_or(KagemushaError::MathOverflow)?;
 
-    msg!("Kagemusha: Withdraw {} from strategy {}", amount, strategy.name_as_str());
+    // Update position, closing it once the user holds no shares
+    let remaining_shares = ctx.accounts.user_share_account.amount - shares;
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        position.redeem(shares)?;
+        if position.lp_shares == 0 && remaining_shares == 0 {
+            position.close(ctx.accounts.user.to_account_info())?;
+        }
+    }
+
+    emit!(Withdrawn {
+        strategy: strategy.key(),
+        user: ctx.accounts.user.key(),
+        shares,
+        fees: fee_split,
+        value,
+        timestamp: now,
+    });
+
+    msg!("Kagemusha: Withdraw {} shares (value {}) from strategy {}", shares, value, strategy.name_as_str());
 
     Ok(())
 }
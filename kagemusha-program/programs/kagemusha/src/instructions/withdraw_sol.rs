@@ -1,25 +1,50 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program;
-use crate::state::{StrategyVault, UserPosition};
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use crate::state::{ProtocolConfig, ReferrerAccount, StrategyVault, UserPosition};
 use crate::errors::KagemushaError;
+use crate::events::Withdrawn;
+use crate::nav;
 
 /// Withdraw native SOL from a strategy vault
+/// Redeems shares at NAV, paid out from the vault's SOL balance. The payout is
+/// capped at the requested shares' pro-rata slice of `vault_sol`; shares the
+/// slice can't cover at NAV are not redeemed and stay with the holder.
+/// Unlike `withdraw`, this needs every basket price to be valid.
+///
+/// Remaining accounts: (vault token account, mint, price feed) for each
+/// composition token, used to price the redeemed shares. The basket must
+/// include wrapped SOL, whose price converts the payout to lamports.
 #[derive(Accounts)]
 pub struct WithdrawSol<'info> {
     #[account(mut)]
     pub strategy: Account<'info, StrategyVault>,
 
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The holder's deposit record, if they deposited. Shares received from
+    /// fee claims or by transfer can be redeemed without one.
     #[account(
         mut,
         seeds = [b"position", strategy.key().as_ref(), user.key().as_ref()],
-        bump,
+        bump = position.bump,
         constraint = position.user == user.key() @ KagemushaError::Unauthorized,
     )]
-    pub position: Account<'info, UserPosition>,
+    pub position: Option<Account<'info, UserPosition>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
 
+    #[account(
+        mut,
+        seeds = [b"referrer", strategy.key().as_ref(), referrer_account.referrer.as_ref()],
+        bump = referrer_account.bump
+    )]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
+
     /// CHECK: This is the vault's SOL account (PDA)
     #[account(
         mut,
@@ -28,17 +53,94 @@ pub struct WithdrawSol<'info> {
     )]
     pub vault_sol: AccountInfo<'info>,
 
+    #[account(
+        mut,
+        address = strategy.share_mint @ KagemushaError::MintMismatch
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = user,
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<WithdrawSol>, amount: u64) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawSol<'info>>,
+    shares: u64,
+) -> Result<()> {
+    // Accrue fees owed up to now before pricing shares
+    let now = Clock::get()?.unix_timestamp;
+    let minted_supply = ctx.accounts.share_mint.supply;
+    ctx.accounts.strategy.accrue_management_fee(minted_supply, now, &ctx.accounts.config)?;
+
     let strategy = &ctx.accounts.strategy;
-    let position = &ctx.accounts.position;
 
-    // Validate strategy is active
-    require!(strategy.is_active, KagemushaError::StrategyInactive);
-    require!(amount > 0, KagemushaError::InsufficientFunds);
-    require!(amount <= position.lp_shares, KagemushaError::InsufficientFunds);
+    // Validate withdrawal amount. Withdrawals are open in every strategy status.
+    require!(shares > 0, KagemushaError::InsufficientFunds);
+    require!(
+        shares <= ctx.accounts.user_share_account.amount,
+        KagemushaError::InsufficientFunds
+    );
+
+    // Value the redeemed shares at NAV, paid in lamports
+    let basket = nav::load_basket(strategy, &ctx.accounts.config, ctx.remaining_accounts)?;
+    let sol_lamports = nav::liquid_lamports(&ctx.accounts.vault_sol)?;
+    let nav_before = nav::compute_nav(&basket, sol_lamports)?;
+    ctx.accounts.strategy.crystallize_performance_fee(
+        nav_before,
+        minted_supply,
+        now,
+        true,
+        &ctx.accounts.config,
+    )?;
+    let total_shares = ctx.accounts.strategy.total_shares(minted_supply)?;
+
+    // Only the slice of vault_sol owed to the requested shares is paid in SOL,
+    // so one redeemer can't drain the vault's liquidity at the oracle price
+    let sol_slice = nav::pro_rata(sol_lamports, shares, total_shares)?;
+    let payable_shares = nav::pro_rata(shares, nav::sol_value(&basket, sol_lamports)?, nav_before)?;
+    let shares = shares.min(payable_shares);
+    require!(shares > 0, KagemushaError::InsufficientLiquidity);
+
+    // Exit fee is taken in shares and split between the fee recipients.
+    // It is waived while an announced composition change is pending.
+    let referred = match ctx.accounts.position.as_deref() {
+        Some(position) => position.check_referrer(ctx.accounts.referrer_account.as_deref())?,
+        None => false,
+    };
+    let exit_fee_bps = ctx.accounts.strategy.effective_exit_fee_bps(now);
+    let (redeemed_shares, fee_split) = ctx.accounts.strategy.charge_share_fee(
+        shares,
+        exit_fee_bps,
+        &ctx.accounts.config,
+        referred,
+    )?;
+    if let Some(referrer_account) = ctx.accounts.referrer_account.as_mut() {
+        referrer_account.credit(fee_split.referrer)?;
+    }
+    let value = nav::pro_rata(nav_before, redeemed_shares, total_shares)?;
+    let amount = nav::native_asset(&basket)?
+        .price
+        .token_amount(value, nav::SOL_DECIMALS)?;
+    require!(amount > 0, KagemushaError::ZeroShares);
+    require!(amount <= sol_slice, KagemushaError::InsufficientLiquidity);
+
+    // Burn the redeemed share tokens
+    let burn_accounts = Burn {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        from: ctx.accounts.user_share_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    token::burn(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts),
+        shares,
+    )?;
 
     // Build PDA signer seeds for vault_sol
     let strategy_key = ctx.accounts.strategy.key();
@@ -69,15 +171,29 @@ pub fn handler(ctx: Context<WithdrawSol>, amount: u64) -> Result<()> {
         signer_seeds,
     )?;
 
-    // Update position
-    let position = &mut ctx.accounts.position;
-    position.lp_shares = position.lp_shares.checked_sub(amount).ok_or(KagemushaError::MathOverflow)?;
+    // Update position, closing it once the user holds no shares
+    let remaining_shares = ctx.accounts.user_share_account.amount - shares;
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        position.redeem(shares)?;
+        if position.lp_shares == 0 && remaining_shares == 0 {
+            position.close(ctx.accounts.user.to_account_info())?;
+        }
+    }
 
-    // Update strategy TVL
+    // Update strategy TVL to the post-withdrawal NAV
     let strategy = &mut ctx.accounts.strategy;
-    strategy.tvl = strategy.tvl.checked_sub(amount).ok_or(KagemushaError::MathOverflow)?;
+    strategy.tvl = nav_before.checked_sub(value).ok_or(KagemushaError::MathOverflow)?;
+
+    emit!(Withdrawn {
+        strategy: strategy.key(),
+        user: ctx.accounts.user.key(),
+        shares,
+        fees: fee_split,
+        value,
+        timestamp: now,
+    });
 
-    msg!("Kagemusha: Withdrew {} lamports from strategy {}", amount, strategy.name_as_str());
+    msg!("Kagemusha: Withdrew {} lamports for {} shares from strategy {}", amount, shares, strategy.name_as_str());
 
     Ok(())
 }
@@ -1,10 +1,15 @@
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
+pub mod fees;
 pub mod instructions;
+pub mod nav;
+pub mod oracle;
 pub mod state;
 
 use instructions::*;
+use state::{DelegationParams, ProposalAction, ProtocolConfigParams, StrategyFeeParams, StrategyStatus};
 
 declare_id!("2kdDnjHHLmHex8v5pk8XgB7ddFeiuBW4Yp5Ykx8JmBLd");
 
@@ -12,31 +17,74 @@ declare_id!("2kdDnjHHLmHex8v5pk8XgB7ddFeiuBW4Yp5Ykx8JmBLd");
 pub mod kagemusha {
     use super::*;
 
+    /// Create the global protocol config.
+    /// Only callable by the program's upgrade authority, who becomes admin.
+    pub fn initialize_protocol(
+        ctx: Context<InitializeProtocol>,
+        params: ProtocolConfigParams,
+    ) -> Result<()> {
+        initialize_protocol::handler(ctx, params)
+    }
+
+    /// Update treasury, fee rates, Jupiter program and pause flags.
+    /// Only callable by the protocol admin.
+    pub fn update_protocol(
+        ctx: Context<UpdateProtocol>,
+        params: ProtocolConfigParams,
+    ) -> Result<()> {
+        update_protocol::handler(ctx, params)
+    }
+
+    /// Hand the protocol admin role to a new key.
+    /// Only callable by the protocol admin.
+    pub fn set_protocol_admin(ctx: Context<SetProtocolAdmin>, new_admin: Pubkey) -> Result<()> {
+        set_protocol_admin::handler(ctx, new_admin)
+    }
+
     /// Initialize a new strategy vault with the given parameters.
     /// 
     /// # Arguments
     /// * `name` - Strategy name (max 32 chars)
     /// * `strategy_type` - 0: Sniper, 1: Fortress, 2: Wave
     /// * `target_weights` - Token weights in basis points (must sum to 10000)
+    /// * `mints` - Mint of each weighted token (no duplicates)
+    /// * `price_feeds` - Pyth price account for each weighted token
     pub fn initialize_strategy(
         ctx: Context<InitializeStrategy>,
         name: String,
         strategy_type: u8,
         target_weights: Vec<u16>,
+        mints: Vec<Pubkey>,
+        price_feeds: Vec<Pubkey>,
     ) -> Result<()> {
-        initialize::handler(ctx, name, strategy_type, target_weights)
+        initialize::handler(ctx, name, strategy_type, target_weights, mints, price_feeds)
+    }
+
+    /// Create the strategy-owned vault token account for a composition mint.
+    /// Only callable by the strategy owner, or by anyone for a mint of a composition shareholders passed.
+    pub fn add_vault_account(ctx: Context<AddVaultAccount>) -> Result<()> {
+        add_vault_account::handler(ctx)
     }
 
     /// Deposit tokens into a strategy vault.
-    /// Creates or updates the user's position.
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        deposit::handler(ctx, amount)
+    /// Open to anyone, or to pass holders if the strategy is private.
+    /// Creates or updates the user's position and mints share tokens.
+    /// `referrer` is recorded when the position is opened and earns part of
+    /// its entry and exit fees.
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        amount: u64,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        deposit::handler(ctx, amount, referrer)
     }
 
     /// Execute a tactical rebalance via Jupiter swap.
-    /// Only callable by the strategy owner.
-    pub fn tactical_rebalance(
-        ctx: Context<Rebalance>,
+    /// Reserves the protocol fee set in the ProtocolConfig in the vault.
+    /// Callable by the strategy owner, its delegated rebalancer, or a session
+    /// key within the limits of its Delegation.
+    pub fn tactical_rebalance<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Rebalance<'info>>,
         amount_in: u64,
         minimum_amount_out: u64,
         route_data: Vec<u8>,
@@ -45,23 +93,278 @@ pub mod kagemusha {
     }
 
     /// Withdraw tokens from strategy vault.
-    /// Callable by position owner.
-    pub fn withdraw(
-        ctx: Context<Withdraw>,
-        amount: u64,
+    /// Burns `shares` and pays out their pro-rata slice of every basket asset.
+    /// Callable by any position owner; the position closes once fully exited.
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
+        shares: u64,
     ) -> Result<()> {
-        withdraw::handler(ctx, amount)
+        withdraw::handler(ctx, shares)
     }
 
     /// Deposit native SOL into a strategy vault.
-    /// Anyone with SOL can deposit.
-    pub fn deposit_sol(ctx: Context<DepositSol>, amount: u64) -> Result<()> {
-        deposit_sol::handler(ctx, amount)
+    /// Open to anyone, or to pass holders if the strategy is private.
+    pub fn deposit_sol<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositSol<'info>>,
+        amount: u64,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        deposit_sol::handler(ctx, amount, referrer)
     }
 
     /// Withdraw native SOL from strategy vault.
-    /// Callable by position owner.
-    pub fn withdraw_sol(ctx: Context<WithdrawSol>, amount: u64) -> Result<()> {
-        withdraw_sol::handler(ctx, amount)
+    /// Burns `shares` and pays their NAV value in lamports.
+    /// Callable by any position owner; the position closes once fully exited.
+    pub fn withdraw_sol<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawSol<'info>>,
+        shares: u64,
+    ) -> Result<()> {
+        withdraw_sol::handler(ctx, shares)
+    }
+
+    /// Make a strategy private (deposits need a DepositorPass) or public.
+    /// Only callable by the strategy owner.
+    pub fn set_private(ctx: Context<SetPrivate>, is_private: bool) -> Result<()> {
+        set_private::handler(ctx, is_private)
+    }
+
+    /// Allow `depositor` to deposit into a private strategy.
+    /// Only callable by the strategy owner or attestor.
+    pub fn add_depositor(ctx: Context<AddDepositor>, depositor: Pubkey) -> Result<()> {
+        add_depositor::handler(ctx, depositor)
+    }
+
+    /// Revoke a depositor's pass. Withdrawals are unaffected.
+    /// Only callable by the strategy owner or attestor.
+    pub fn remove_depositor(ctx: Context<RemoveDepositor>) -> Result<()> {
+        remove_depositor::handler(ctx)
+    }
+
+    /// Set the strategy's fee rates, accruing fees owed at the old rates.
+    /// Increases are staged for `execute_fees`. Only callable by the strategy owner.
+    pub fn set_strategy_fees(ctx: Context<SetStrategyFees>, params: StrategyFeeParams) -> Result<()> {
+        set_strategy_fees::handler(ctx, params)
+    }
+
+    /// Mint the curator's accrued fee shares and pay its swap fees to the
+    /// strategy owner. Only callable by the strategy owner.
+    pub fn claim_curator_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimCuratorFees<'info>>,
+    ) -> Result<()> {
+        claim_curator_fees::handler(ctx)
+    }
+
+    /// Pay a strategy's protocol fee shares and reserved swap fees to the treasury.
+    /// Only callable by the protocol admin.
+    pub fn claim_protocol_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimProtocolFees<'info>>,
+    ) -> Result<()> {
+        claim_protocol_fees::handler(ctx)
+    }
+
+    /// Open a ReferrerAccount for the signer in a strategy.
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        register_referrer::handler(ctx)
+    }
+
+    /// Mint the referral fee shares a referrer has earned in a strategy.
+    /// Only callable by the referrer.
+    pub fn claim_referrer_fees(ctx: Context<ClaimReferrerFees>) -> Result<()> {
+        claim_referrer_fees::handler(ctx)
+    }
+
+    /// Offer the strategy to a new owner, who must accept it.
+    /// Only callable by the strategy owner.
+    pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+        propose_owner::handler(ctx, new_owner)
+    }
+
+    /// Take over a strategy offered with `propose_owner`.
+    /// Only callable by the pending owner.
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        accept_owner::handler(ctx)
+    }
+
+    /// Withdraw a pending ownership offer.
+    /// Only callable by the strategy owner.
+    pub fn cancel_owner_transfer(ctx: Context<CancelOwnerTransfer>) -> Result<()> {
+        cancel_owner_transfer::handler(ctx)
+    }
+
+    /// Set or clear the key allowed to rebalance on the owner's behalf.
+    /// Only callable by the strategy owner.
+    pub fn set_rebalancer(ctx: Context<SetRebalancer>, rebalancer: Pubkey) -> Result<()> {
+        set_rebalancer::handler(ctx, rebalancer)
+    }
+
+    /// Create or update a session key's rebalance delegation.
+    /// Only callable by the strategy owner, on strategies without governance.
+    pub fn set_delegation(
+        ctx: Context<SetDelegation>,
+        delegate: Pubkey,
+        params: DelegationParams,
+    ) -> Result<()> {
+        set_delegation::handler(ctx, delegate, params)
+    }
+
+    /// Revoke a session key's rebalance delegation.
+    /// Only callable by the strategy owner.
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> Result<()> {
+        revoke_delegation::handler(ctx)
+    }
+
+    /// Pause deposits, go withdraw-only, shut down or reactivate a strategy.
+    /// Only callable by the strategy owner, and not while frozen.
+    pub fn set_strategy_status(ctx: Context<SetStrategyStatus>, status: StrategyStatus) -> Result<()> {
+        set_strategy_status::handler(ctx, status)
+    }
+
+    /// Retire an empty strategy: sweep dust to the owner, close the vault
+    /// accounts and return their rent. Only callable by the strategy owner.
+    pub fn close_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseStrategy<'info>>,
+    ) -> Result<()> {
+        close_strategy::handler(ctx)
+    }
+
+    /// Announce a new composition, executable after the weights timelock.
+    /// Only callable by the strategy owner.
+    pub fn propose_weights(
+        ctx: Context<ProposeWeights>,
+        target_weights: Vec<u16>,
+        mints: Vec<Pubkey>,
+        price_feeds: Vec<Pubkey>,
+    ) -> Result<()> {
+        propose_weights::handler(ctx, target_weights, mints, price_feeds)
+    }
+
+    /// Apply the pending composition once its timelock has elapsed.
+    /// Only callable by the strategy owner, or by anyone if shareholders passed it.
+    pub fn execute_weights(ctx: Context<ExecuteWeights>) -> Result<()> {
+        execute_weights::handler(ctx)
+    }
+
+    /// Withdraw the pending composition change unless shareholders passed it.
+    /// Only callable by the strategy owner.
+    pub fn cancel_weights(ctx: Context<CancelWeights>) -> Result<()> {
+        cancel_weights::handler(ctx)
+    }
+
+    /// Set the delay between proposing and executing a composition change.
+    /// Only callable by the strategy owner.
+    pub fn set_weights_timelock(ctx: Context<SetWeightsTimelock>, timelock: i64) -> Result<()> {
+        set_weights_timelock::handler(ctx, timelock)
+    }
+
+    /// Announce a rebalance that moves away from the target weights,
+    /// executable after the weights timelock. Only callable by the strategy owner.
+    pub fn announce_rebalance(
+        ctx: Context<AnnounceRebalance>,
+        mint_in: Pubkey,
+        mint_out: Pubkey,
+        max_amount_in: u64,
+    ) -> Result<()> {
+        announce_rebalance::handler(ctx, mint_in, mint_out, max_amount_in)
+    }
+
+    /// Withdraw an announced off-target rebalance.
+    /// Only callable by the strategy owner.
+    pub fn cancel_rebalance(ctx: Context<CancelRebalance>) -> Result<()> {
+        cancel_rebalance::handler(ctx)
+    }
+
+    /// Freeze a strategy into withdraw-only mode.
+    /// Only callable by the protocol guardian.
+    pub fn guardian_freeze(ctx: Context<GuardianFreeze>) -> Result<()> {
+        guardian_freeze::handler(ctx)
+    }
+
+    /// Lift a guardian freeze and set the strategy's status.
+    /// Only callable by the protocol admin.
+    pub fn unfreeze_strategy(ctx: Context<UnfreezeStrategy>, status: StrategyStatus) -> Result<()> {
+        unfreeze_strategy::handler(ctx, status)
+    }
+
+    /// Remove a strategy's delegated rebalancer.
+    /// Only callable by the protocol guardian.
+    pub fn guardian_revoke_rebalancer(ctx: Context<GuardianRevokeRebalancer>) -> Result<()> {
+        guardian_revoke_rebalancer::handler(ctx)
+    }
+
+    /// Close a strategy's session delegation.
+    /// Only callable by the protocol guardian.
+    pub fn guardian_revoke_delegation(ctx: Context<GuardianRevokeDelegation>) -> Result<()> {
+        guardian_revoke_delegation::handler(ctx)
+    }
+
+    /// Approve a mint for strategy baskets, recording its oracle and risk tier.
+    /// Only callable by the protocol admin.
+    pub fn set_mint_registry(ctx: Context<SetMintRegistry>, risk_tier: u8) -> Result<()> {
+        set_mint_registry::handler(ctx, risk_tier)
+    }
+
+    /// Withdraw a mint's approval.
+    /// Only callable by the protocol admin.
+    pub fn remove_mint_registry(ctx: Context<RemoveMintRegistry>) -> Result<()> {
+        remove_mint_registry::handler(ctx)
+    }
+
+    /// Set or clear the key allowed to issue and revoke depositor passes.
+    /// Only callable by the strategy owner.
+    pub fn set_attestor(ctx: Context<SetAttestor>, attestor: Pubkey) -> Result<()> {
+        set_attestor::handler(ctx, attestor)
+    }
+
+    /// Put the strategy under shareholder governance.
+    /// Only callable by the strategy owner.
+    pub fn set_governance(
+        ctx: Context<SetGovernance>,
+        quorum_bps: u16,
+        threshold_bps: u16,
+        voting_period: i64,
+    ) -> Result<()> {
+        set_governance::handler(ctx, quorum_bps, threshold_bps, voting_period)
+    }
+
+    /// Put a weight update, fee change or new rebalancer to a shareholder vote.
+    /// Only callable by the strategy owner.
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+        create_proposal::handler(ctx, action)
+    }
+
+    /// Vote on a proposal with share tokens, locked until voting ends.
+    /// Callable by any shareholder with a position from before the proposal, once per proposal.
+    pub fn cast_vote(ctx: Context<CastVote>, amount: u64, approve: bool) -> Result<()> {
+        cast_vote::handler(ctx, amount, approve)
+    }
+
+    /// Apply a proposal that passed once voting has ended.
+    /// Callable by anyone.
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        execute_proposal::handler(ctx)
+    }
+
+    /// Withdraw a proposal that has not been executed.
+    /// Only callable by the strategy owner.
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        cancel_proposal::handler(ctx)
+    }
+
+    /// Return the share tokens locked by a vote once voting is over.
+    /// Only callable by the voter.
+    pub fn reclaim_vote(ctx: Context<ReclaimVote>) -> Result<()> {
+        reclaim_vote::handler(ctx)
+    }
+
+    /// Apply the pending fee increase once its timelock has elapsed.
+    /// Only callable by the strategy owner, or by anyone if shareholders passed it.
+    pub fn execute_fees(ctx: Context<ExecuteFees>) -> Result<()> {
+        execute_fees::handler(ctx)
+    }
+
+    /// Withdraw the pending fee increase unless shareholders passed it.
+    /// Only callable by the strategy owner.
+    pub fn cancel_fees(ctx: Context<CancelFees>) -> Result<()> {
+        cancel_fees::handler(ctx)
     }
 }
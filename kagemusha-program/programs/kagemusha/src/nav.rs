@@ -0,0 +1,383 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, spl_token, Mint, TokenAccount};
+use crate::errors::KagemushaError;
+use crate::oracle::{self, OraclePrice};
+use crate::state::{ProtocolConfig, StrategyVault};
+
+/// Decimals of native SOL (lamports)
+pub const SOL_DECIMALS: u8 = 9;
+
+/// Number of remaining accounts per basket token: vault token account, mint, price feed
+pub const ACCOUNTS_PER_ASSET: usize = 3;
+
+/// One basket token account held by a strategy vault.
+/// `amount` excludes fees reserved for fee recipients.
+pub struct BasketHolding<'info> {
+    pub info: AccountInfo<'info>,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+/// One basket token account held by a strategy vault, with its oracle price.
+/// `amount` excludes fees reserved for fee recipients.
+pub struct BasketAsset<'info> {
+    pub info: AccountInfo<'info>,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub decimals: u8,
+    pub price: OraclePrice,
+}
+
+impl BasketAsset<'_> {
+    /// USD value (6 decimals) of the vault's balance of this asset.
+    pub fn value(&self) -> Result<u64> {
+        self.price.usd_value(self.amount, self.decimals)
+    }
+}
+
+/// Loads the strategy's basket balances from the front of `accounts`,
+/// without reading prices. Expects `num_tokens` triples of (vault token
+/// account, mint, price feed) matching the accounts recorded for each slot
+/// of the composition; the price feeds are skipped.
+pub fn load_holdings<'info>(
+    strategy: &Account<StrategyVault>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<Vec<BasketHolding<'info>>> {
+    let num_tokens = strategy.num_tokens as usize;
+    require!(
+        accounts.len() >= num_tokens * ACCOUNTS_PER_ASSET,
+        KagemushaError::InvalidBasketAccounts
+    );
+
+    let mut holdings: Vec<BasketHolding<'info>> = Vec::with_capacity(num_tokens);
+    for (i, chunk) in accounts
+        .chunks(ACCOUNTS_PER_ASSET)
+        .take(num_tokens)
+        .enumerate()
+    {
+        let (info, mint_info) = (&chunk[0], &chunk[1]);
+
+        require!(
+            info.key() == strategy.vault_token_accounts[i],
+            KagemushaError::InvalidVaultAccount
+        );
+        require!(info.owner == &token::ID, KagemushaError::InvalidBasketAccounts);
+        let token_account = TokenAccount::try_deserialize(&mut &info.data.borrow()[..])?;
+        require!(
+            token_account.owner == strategy.key(),
+            KagemushaError::InvalidVaultAccount
+        );
+
+        require!(mint_info.key() == strategy.mints[i], KagemushaError::MintMismatch);
+        require!(mint_info.key() == token_account.mint, KagemushaError::MintMismatch);
+        require!(mint_info.owner == &token::ID, KagemushaError::MintMismatch);
+        let mint = Mint::try_deserialize(&mut &mint_info.data.borrow()[..])?;
+
+        holdings.push(BasketHolding {
+            info: info.clone(),
+            mint: token_account.mint,
+            amount: token_account.amount.saturating_sub(strategy.reserved_fees(i)?),
+            decimals: mint.decimals,
+        });
+    }
+
+    Ok(holdings)
+}
+
+/// Loads the strategy's basket from the front of `accounts` and prices it.
+/// Expects the same triples as `load_holdings`. Prices must come from the
+/// Pyth program set in `config`.
+pub fn load_basket<'info>(
+    strategy: &Account<StrategyVault>,
+    config: &ProtocolConfig,
+    accounts: &[AccountInfo<'info>],
+) -> Result<Vec<BasketAsset<'info>>> {
+    let now = Clock::get()?.unix_timestamp;
+    let holdings = load_holdings(strategy, accounts)?;
+
+    let mut basket: Vec<BasketAsset<'info>> = Vec::with_capacity(holdings.len());
+    for (i, (holding, chunk)) in holdings
+        .into_iter()
+        .zip(accounts.chunks(ACCOUNTS_PER_ASSET))
+        .enumerate()
+    {
+        let price_info = &chunk[2];
+        require!(
+            price_info.key() == strategy.price_feeds[i],
+            KagemushaError::InvalidPriceFeed
+        );
+        let price = oracle::load_price(price_info, &config.pyth_program, now)?;
+
+        basket.push(BasketAsset {
+            info: holding.info,
+            mint: holding.mint,
+            amount: holding.amount,
+            decimals: holding.decimals,
+            price,
+        });
+    }
+
+    Ok(basket)
+}
+
+/// Fixed-point scale of basket weights used for drift (parts per million)
+pub const WEIGHT_SCALE: u64 = 1_000_000;
+
+/// Distance of the basket from the strategy's target weights: the sum over
+/// all tokens of |current weight - target weight|, scaled by `WEIGHT_SCALE`.
+/// Liquid SOL in `vault_sol` has no target and is left out.
+pub fn target_drift(strategy: &StrategyVault, basket: &[BasketAsset]) -> Result<u64> {
+    let values = basket
+        .iter()
+        .map(|asset| asset.value())
+        .collect::<Result<Vec<u64>>>()?;
+    let total: u128 = values.iter().map(|&v| v as u128).sum();
+    if total == 0 {
+        return Ok(0);
+    }
+
+    let mut drift: u64 = 0;
+    for (i, &value) in values.iter().enumerate() {
+        let weight = (value as u128 * WEIGHT_SCALE as u128 / total) as u64;
+        let target = strategy.target_weights[i] as u64 * WEIGHT_SCALE / 10_000;
+        drift = drift
+            .checked_add(weight.abs_diff(target))
+            .ok_or(KagemushaError::MathOverflow)?;
+    }
+    Ok(drift)
+}
+
+/// Transfers reserved fee tokens out of the strategy's vault token accounts.
+/// Expects `num_tokens` pairs of (vault token account, recipient token
+/// account) at the front of `accounts`. Recipient token accounts must be
+/// owned by `recipient`; slots with nothing owed are skipped.
+pub fn pay_reserved_fees<'info>(
+    strategy: &Account<'info, StrategyVault>,
+    amounts: &[u64; 10],
+    accounts: &[AccountInfo<'info>],
+    recipient: &Pubkey,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let num_tokens = strategy.num_tokens as usize;
+    require!(accounts.len() >= num_tokens * 2, KagemushaError::InvalidBasketAccounts);
+
+    let seeds: &[&[u8]] = &[
+        b"strategy",
+        strategy.creator.as_ref(),
+        strategy.name_seed(),
+        &[strategy.bump]
+    ];
+    let signer_seeds = &[seeds];
+
+    for (i, pair) in accounts.chunks(2).take(num_tokens).enumerate() {
+        let (vault_info, recipient_info) = (&pair[0], &pair[1]);
+        if amounts[i] == 0 {
+            continue;
+        }
+
+        require!(
+            vault_info.key() == strategy.vault_token_accounts[i],
+            KagemushaError::InvalidVaultAccount
+        );
+        let recipient_account = TokenAccount::try_deserialize(&mut &recipient_info.data.borrow()[..])?;
+        require!(recipient_account.owner == *recipient, KagemushaError::InvalidFeeAccount);
+        require!(recipient_account.mint == strategy.mints[i], KagemushaError::MintMismatch);
+
+        let transfer_accounts = token::Transfer {
+            from: vault_info.clone(),
+            to: recipient_info.clone(),
+            authority: strategy.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(token_program.clone(), transfer_accounts, signer_seeds),
+            amounts[i],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Lamports in `vault_sol` above its rent-exempt minimum.
+pub fn liquid_lamports(vault_sol: &AccountInfo) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    Ok(vault_sol.lamports().saturating_sub(rent_exempt))
+}
+
+/// The basket slot holding wrapped SOL, whose price also values `vault_sol`.
+pub fn native_asset<'a, 'info>(basket: &'a [BasketAsset<'info>]) -> Result<&'a BasketAsset<'info>> {
+    basket
+        .iter()
+        .find(|asset| asset.mint == spl_token::native_mint::ID)
+        .ok_or_else(|| error!(KagemushaError::NativeMintNotInBasket))
+}
+
+/// USD value (6 decimals) of `lamports` held in `vault_sol`.
+pub fn sol_value(basket: &[BasketAsset], lamports: u64) -> Result<u64> {
+    if lamports == 0 {
+        return Ok(0);
+    }
+    native_asset(basket)?.price.usd_value(lamports, SOL_DECIMALS)
+}
+
+/// Net asset value of the vault in USD (6 decimals): every basket balance
+/// plus liquid SOL. Liquid SOL is only counted when wrapped SOL is in the
+/// basket to price it; otherwise it is left out, so lamports sent to
+/// `vault_sol` can't make the vault unpriceable.
+pub fn compute_nav(basket: &[BasketAsset], sol_lamports: u64) -> Result<u64> {
+    let sol = if basket.iter().any(|asset| asset.mint == spl_token::native_mint::ID) {
+        sol_value(basket, sol_lamports)?
+    } else {
+        0
+    };
+    basket.iter().try_fold(sol, |nav, asset| {
+        nav.checked_add(asset.value()?)
+            .ok_or_else(|| error!(KagemushaError::MathOverflow))
+    })
+}
+
+/// Smallest USD value (6 decimals) of the first deposit into a vault with no
+/// shares. Keeps the initial share supply large enough that donations to the
+/// vault can't round later deposits down to nothing.
+pub const MIN_INITIAL_DEPOSIT_VALUE: u64 = 1_000_000;
+
+/// Shares to mint for a deposit of `value` into a vault worth `nav`.
+/// The first deposit into a vault with no shares mints shares 1:1 with the
+/// USD value of the whole vault after the deposit, so any assets left in it
+/// are priced in and the share price starts at 1.0.
+pub fn shares_for_deposit(value: u64, total_shares: u64, nav: u64) -> Result<u64> {
+    if total_shares == 0 {
+        require!(value >= MIN_INITIAL_DEPOSIT_VALUE, KagemushaError::DepositTooSmall);
+        return value
+            .checked_add(nav)
+            .ok_or_else(|| error!(KagemushaError::MathOverflow));
+    }
+    require!(nav > 0, KagemushaError::InsufficientFunds);
+    mul_div(value, total_shares, nav)
+}
+
+/// `amount * shares / total_shares`, rounded down so the vault never overpays.
+pub fn pro_rata(amount: u64, shares: u64, total_shares: u64) -> Result<u64> {
+    require!(total_shares > 0, KagemushaError::InsufficientFunds);
+    mul_div(amount, shares, total_shares)
+}
+
+fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    let result = (a as u128)
+        .checked_mul(b as u128)
+        .ok_or(KagemushaError::MathOverflow)?
+        .checked_div(c as u128)
+        .ok_or(KagemushaError::MathOverflow)?;
+    u64::try_from(result).map_err(|_| error!(KagemushaError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SOL at 150.00 USD (Pyth exponent -8)
+    const SOL_PRICE: OraclePrice = OraclePrice {
+        price: 15_000_000_000,
+        conf: 0,
+        expo: -8,
+        publish_time: 0,
+    };
+
+    /// USDC at 1.00 USD (Pyth exponent -8)
+    const USDC_PRICE: OraclePrice = OraclePrice {
+        price: 100_000_000,
+        conf: 0,
+        expo: -8,
+        publish_time: 0,
+    };
+
+    fn asset<'info>(
+        info: AccountInfo<'info>,
+        mint: Pubkey,
+        amount: u64,
+        decimals: u8,
+        price: OraclePrice,
+    ) -> BasketAsset<'info> {
+        BasketAsset { info, mint, amount, decimals, price }
+    }
+
+    #[test]
+    fn first_deposit_mints_one_share_per_usd() {
+        assert_eq!(shares_for_deposit(5_000_000, 0, 0).unwrap(), 5_000_000);
+    }
+
+    #[test]
+    fn first_deposit_prices_in_assets_left_in_the_vault() {
+        // 2 USD of dust or donations already in a vault with no shares
+        assert_eq!(shares_for_deposit(1_000_000, 0, 2_000_000).unwrap(), 3_000_000);
+    }
+
+    #[test]
+    fn first_deposit_below_minimum_is_rejected() {
+        assert!(shares_for_deposit(MIN_INITIAL_DEPOSIT_VALUE - 1, 0, 0).is_err());
+        assert!(shares_for_deposit(1, 0, 1_000_000_000).is_err());
+    }
+
+    #[test]
+    fn donation_cannot_round_later_deposits_to_zero() {
+        // Attacker holds the minimum first deposit, then donates 1,000 USD
+        let total_shares = shares_for_deposit(MIN_INITIAL_DEPOSIT_VALUE, 0, 0).unwrap();
+        let nav = MIN_INITIAL_DEPOSIT_VALUE + 1_000_000_000;
+        let shares = shares_for_deposit(10_000_000, total_shares, nav).unwrap();
+        assert_eq!(shares, 9_990);
+    }
+
+    #[test]
+    fn later_deposits_are_priced_at_nav_per_share() {
+        // 1,000 shares worth 1,500 USD: 300 USD buys 200 shares
+        let shares = shares_for_deposit(300_000_000, 1_000_000_000, 1_500_000_000).unwrap();
+        assert_eq!(shares, 200_000_000);
+    }
+
+    #[test]
+    fn deposit_into_worthless_vault_with_shares_is_rejected() {
+        assert!(shares_for_deposit(1_000_000, 1_000_000, 0).is_err());
+    }
+
+    #[test]
+    fn pro_rata_rounds_down() {
+        assert_eq!(pro_rata(100, 1, 3).unwrap(), 33);
+        assert_eq!(pro_rata(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(pro_rata(0, 5, 10).unwrap(), 0);
+    }
+
+    #[test]
+    fn pro_rata_rejects_empty_supply() {
+        assert!(pro_rata(100, 1, 0).is_err());
+    }
+
+    #[test]
+    fn compute_nav_sums_basket_values_and_liquid_sol() {
+        let (key_a, key_b, owner) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::default());
+        let (mut lamports_a, mut lamports_b) = (0u64, 0u64);
+        let (mut data_a, mut data_b) = (Vec::<u8>::new(), Vec::<u8>::new());
+        let info_a = AccountInfo::new(&key_a, false, false, &mut lamports_a, &mut data_a, &owner, false, 0);
+        let info_b = AccountInfo::new(&key_b, false, false, &mut lamports_b, &mut data_b, &owner, false, 0);
+
+        // 2 wrapped SOL and 100 USDC, plus 1 SOL liquid in vault_sol
+        let basket = vec![
+            asset(info_a, spl_token::native_mint::ID, 2_000_000_000, 9, SOL_PRICE),
+            asset(info_b, Pubkey::new_unique(), 100_000_000, 6, USDC_PRICE),
+        ];
+        assert_eq!(compute_nav(&basket, 0).unwrap(), 400_000_000);
+        assert_eq!(compute_nav(&basket, 1_000_000_000).unwrap(), 550_000_000);
+    }
+
+    #[test]
+    fn compute_nav_ignores_liquid_sol_without_wrapped_sol() {
+        let (key, owner) = (Pubkey::new_unique(), Pubkey::default());
+        let mut lamports = 0u64;
+        let mut data = Vec::<u8>::new();
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        let basket = vec![asset(info, Pubkey::new_unique(), 100_000_000, 6, USDC_PRICE)];
+        assert_eq!(compute_nav(&basket, 0).unwrap(), 100_000_000);
+        assert_eq!(compute_nav(&basket, 1_000_000_000).unwrap(), 100_000_000);
+        assert!(sol_value(&basket, 1).is_err());
+    }
+}
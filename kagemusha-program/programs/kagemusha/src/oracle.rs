@@ -0,0 +1,256 @@
+//! Minimal reader for Pyth v2 price accounts.
+//!
+//! Price accounts are parsed straight from account data, so program-test can
+//! use locally crafted accounts owned by the Pyth program set in
+//! `ProtocolConfig`.
+
+use anchor_lang::prelude::*;
+use crate::errors::KagemushaError;
+
+/// Maximum age of a price update before it is rejected
+pub const MAX_PRICE_AGE_SECS: i64 = 60;
+
+/// Maximum confidence interval as a fraction of price (200 = 2%)
+pub const MAX_CONFIDENCE_BPS: u64 = 200;
+
+/// Decimals of all USD values produced by this module (USDC-like)
+pub const USD_DECIMALS: u8 = 6;
+
+/// Range of accepted price exponents
+pub const MIN_PRICE_EXPO: i32 = -12;
+pub const MAX_PRICE_EXPO: i32 = 0;
+
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_VERSION_2: u32 = 2;
+const PYTH_ACCOUNT_TYPE_PRICE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+
+// Byte offsets into a Pyth v2 price account
+const OFFSET_MAGIC: usize = 0;
+const OFFSET_VERSION: usize = 4;
+const OFFSET_ACCOUNT_TYPE: usize = 8;
+const OFFSET_EXPO: usize = 20;
+const OFFSET_TIMESTAMP: usize = 96;
+const OFFSET_AGG_PRICE: usize = 208;
+const OFFSET_AGG_CONF: usize = 216;
+const OFFSET_AGG_STATUS: usize = 224;
+const PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+/// A validated aggregate price: `price * 10^expo` USD per whole token.
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+/// Reads and validates the aggregate price of a Pyth price account owned by
+/// `pyth_program`. Rejects prices that are not trading, stale, or too uncertain.
+pub fn load_price(info: &AccountInfo, pyth_program: &Pubkey, now: i64) -> Result<OraclePrice> {
+    require!(info.owner == pyth_program, KagemushaError::InvalidPriceFeed);
+
+    let data = info.try_borrow_data()?;
+    require!(data.len() >= PRICE_ACCOUNT_MIN_LEN, KagemushaError::InvalidPriceFeed);
+    require!(read_u32(&data, OFFSET_MAGIC) == PYTH_MAGIC, KagemushaError::InvalidPriceFeed);
+    require!(read_u32(&data, OFFSET_VERSION) == PYTH_VERSION_2, KagemushaError::InvalidPriceFeed);
+    require!(
+        read_u32(&data, OFFSET_ACCOUNT_TYPE) == PYTH_ACCOUNT_TYPE_PRICE,
+        KagemushaError::InvalidPriceFeed
+    );
+    require!(
+        read_u32(&data, OFFSET_AGG_STATUS) == PYTH_STATUS_TRADING,
+        KagemushaError::InvalidPriceFeed
+    );
+
+    let expo = read_u32(&data, OFFSET_EXPO) as i32;
+    let publish_time = read_u64(&data, OFFSET_TIMESTAMP) as i64;
+    let price = read_u64(&data, OFFSET_AGG_PRICE) as i64;
+    let conf = read_u64(&data, OFFSET_AGG_CONF);
+
+    require!(price > 0, KagemushaError::InvalidPriceFeed);
+    require!(
+        (MIN_PRICE_EXPO..=MAX_PRICE_EXPO).contains(&expo),
+        KagemushaError::InvalidPriceFeed
+    );
+    require!(
+        now.saturating_sub(publish_time) <= MAX_PRICE_AGE_SECS,
+        KagemushaError::StalePrice
+    );
+
+    let price = price as u64;
+    require!(
+        (conf as u128) * 10_000 <= (price as u128) * (MAX_CONFIDENCE_BPS as u128),
+        KagemushaError::PriceConfidenceTooWide
+    );
+
+    Ok(OraclePrice { price, conf, expo, publish_time })
+}
+
+impl OraclePrice {
+    /// USD value (6 decimals) of `amount` base units of a token with `decimals`.
+    pub fn usd_value(&self, amount: u64, decimals: u8) -> Result<u64> {
+        let raw = (amount as u128)
+            .checked_mul(self.price as u128)
+            .ok_or(KagemushaError::MathOverflow)?;
+        let value = scale(raw, self.expo + USD_DECIMALS as i32 - decimals as i32)?;
+        u64::try_from(value).map_err(|_| error!(KagemushaError::MathOverflow))
+    }
+
+    /// Base units of a token with `decimals` worth `value` USD (6 decimals).
+    pub fn token_amount(&self, value: u64, decimals: u8) -> Result<u64> {
+        let raw = scale(value as u128, decimals as i32 - USD_DECIMALS as i32 - self.expo)?;
+        let amount = raw
+            .checked_div(self.price as u128)
+            .ok_or(KagemushaError::MathOverflow)?;
+        u64::try_from(amount).map_err(|_| error!(KagemushaError::MathOverflow))
+    }
+}
+
+/// `value * 10^exponent`, rounding down for negative exponents.
+fn scale(value: u128, exponent: i32) -> Result<u128> {
+    let factor = 10u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(KagemushaError::MathOverflow)?;
+    if exponent >= 0 {
+        Ok(value.checked_mul(factor).ok_or(KagemushaError::MathOverflow)?)
+    } else {
+        Ok(value / factor)
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// A Pyth v2 price account with the given aggregate price
+    fn price_account(price: i64, conf: u64, expo: i32, publish_time: i64) -> Vec<u8> {
+        let mut data = vec![0u8; PRICE_ACCOUNT_MIN_LEN];
+        data[OFFSET_MAGIC..OFFSET_MAGIC + 4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[OFFSET_VERSION..OFFSET_VERSION + 4].copy_from_slice(&PYTH_VERSION_2.to_le_bytes());
+        data[OFFSET_ACCOUNT_TYPE..OFFSET_ACCOUNT_TYPE + 4]
+            .copy_from_slice(&PYTH_ACCOUNT_TYPE_PRICE.to_le_bytes());
+        data[OFFSET_EXPO..OFFSET_EXPO + 4].copy_from_slice(&expo.to_le_bytes());
+        data[OFFSET_TIMESTAMP..OFFSET_TIMESTAMP + 8].copy_from_slice(&publish_time.to_le_bytes());
+        data[OFFSET_AGG_PRICE..OFFSET_AGG_PRICE + 8].copy_from_slice(&price.to_le_bytes());
+        data[OFFSET_AGG_CONF..OFFSET_AGG_CONF + 8].copy_from_slice(&conf.to_le_bytes());
+        data[OFFSET_AGG_STATUS..OFFSET_AGG_STATUS + 4]
+            .copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+        data
+    }
+
+    fn load(mut data: Vec<u8>, owner: &Pubkey, pyth_program: &Pubkey) -> Result<OraclePrice> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, owner, false, 0);
+        load_price(&info, pyth_program, NOW)
+    }
+
+    fn price(price: u64, expo: i32) -> OraclePrice {
+        OraclePrice { price, conf: 0, expo, publish_time: NOW }
+    }
+
+    #[test]
+    fn loads_a_fresh_trading_price() {
+        let pyth = Pubkey::new_unique();
+        let loaded = load(price_account(15_000_000_000, 1_000_000, -8, NOW - 10), &pyth, &pyth).unwrap();
+        assert_eq!(loaded.price, 15_000_000_000);
+        assert_eq!(loaded.conf, 1_000_000);
+        assert_eq!(loaded.expo, -8);
+        assert_eq!(loaded.publish_time, NOW - 10);
+    }
+
+    #[test]
+    fn rejects_account_not_owned_by_pyth_program() {
+        let pyth = Pubkey::new_unique();
+        let data = price_account(15_000_000_000, 0, -8, NOW);
+        assert!(load(data, &Pubkey::new_unique(), &pyth).is_err());
+    }
+
+    #[test]
+    fn rejects_stale_price() {
+        let pyth = Pubkey::new_unique();
+        assert!(load(price_account(100, 0, -2, NOW - MAX_PRICE_AGE_SECS), &pyth, &pyth).is_ok());
+        assert!(load(price_account(100, 0, -2, NOW - MAX_PRICE_AGE_SECS - 1), &pyth, &pyth).is_err());
+    }
+
+    #[test]
+    fn rejects_confidence_above_two_percent() {
+        let pyth = Pubkey::new_unique();
+        assert!(load(price_account(10_000, 200, -2, NOW), &pyth, &pyth).is_ok());
+        assert!(load(price_account(10_000, 201, -2, NOW), &pyth, &pyth).is_err());
+    }
+
+    #[test]
+    fn rejects_zero_and_negative_prices() {
+        let pyth = Pubkey::new_unique();
+        assert!(load(price_account(0, 0, -8, NOW), &pyth, &pyth).is_err());
+        assert!(load(price_account(-1, 0, -8, NOW), &pyth, &pyth).is_err());
+    }
+
+    #[test]
+    fn rejects_exponent_out_of_range() {
+        let pyth = Pubkey::new_unique();
+        assert!(load(price_account(100, 0, MIN_PRICE_EXPO, NOW), &pyth, &pyth).is_ok());
+        assert!(load(price_account(100, 0, MAX_PRICE_EXPO, NOW), &pyth, &pyth).is_ok());
+        assert!(load(price_account(100, 0, MIN_PRICE_EXPO - 1, NOW), &pyth, &pyth).is_err());
+        assert!(load(price_account(100, 0, MAX_PRICE_EXPO + 1, NOW), &pyth, &pyth).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_accounts() {
+        let pyth = Pubkey::new_unique();
+        let mut data = price_account(100, 0, -2, NOW);
+        data[OFFSET_MAGIC] ^= 1;
+        assert!(load(data, &pyth, &pyth).is_err());
+
+        let mut data = price_account(100, 0, -2, NOW);
+        data[OFFSET_AGG_STATUS] = 0;
+        assert!(load(data, &pyth, &pyth).is_err());
+
+        let data = price_account(100, 0, -2, NOW)[..PRICE_ACCOUNT_MIN_LEN - 1].to_vec();
+        assert!(load(data, &pyth, &pyth).is_err());
+    }
+
+    #[test]
+    fn usd_value_scales_across_decimals_and_exponents() {
+        // 1.5 SOL (9 decimals) at 150.00000000 USD
+        assert_eq!(price(15_000_000_000, -8).usd_value(1_500_000_000, 9).unwrap(), 225_000_000);
+        // 2.5 USDC (6 decimals) at 1.00 USD with exponent -2
+        assert_eq!(price(100, -2).usd_value(2_500_000, 6).unwrap(), 2_500_000);
+        // 3 whole units of a 0-decimal token at 7 USD with exponent 0
+        assert_eq!(price(7, 0).usd_value(3, 0).unwrap(), 21_000_000);
+        // 1 BONK-like base unit (5 decimals) at 0.00002 USD rounds down to zero
+        assert_eq!(price(2_000, -8).usd_value(1, 5).unwrap(), 0);
+    }
+
+    #[test]
+    fn token_amount_inverts_usd_value_rounding_down() {
+        // 225 USD buys 1.5 SOL at 150 USD
+        assert_eq!(price(15_000_000_000, -8).token_amount(225_000_000, 9).unwrap(), 1_500_000_000);
+        // 2.5 USD buys 2.5 USDC
+        assert_eq!(price(100, -2).token_amount(2_500_000, 6).unwrap(), 2_500_000);
+        // 10 USD at 3 USD per 0-decimal token buys 3 whole tokens
+        assert_eq!(price(3, 0).token_amount(10_000_000, 0).unwrap(), 3);
+    }
+
+    #[test]
+    fn usd_value_reports_overflow() {
+        assert!(price(u64::MAX, 0).usd_value(u64::MAX, 0).is_err());
+    }
+}
+
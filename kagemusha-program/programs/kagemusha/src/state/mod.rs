@@ -1,11 +1,13 @@
 use anchor_lang::prelude::*;
+use crate::errors::KagemushaError;
+use crate::fees::{self, FeeSplit};
 
 /// The core account that stores a user's strategy configuration.
-/// Each strategy is a PDA derived from the owner's pubkey and strategy name.
+/// Each strategy is a PDA derived from the creator's pubkey and strategy name.
 #[account]
 #[derive(Default)]
 pub struct StrategyVault {
-    /// The owner (Shogun) who created this strategy
+    /// The owner (Shogun) who controls this strategy
     pub owner: Pubkey,
     
     /// Human-readable name (max 32 chars)
@@ -21,10 +23,10 @@ pub struct StrategyVault {
     /// Number of active tokens in the composition
     pub num_tokens: u8,
     
-    /// Whether the strategy is actively rebalancing
-    pub is_active: bool,
+    /// Lifecycle status, gating deposits and rebalances
+    pub status: StrategyStatus,
     
-    /// Total value locked in the vault (in lamports equivalent)
+    /// Total value locked in the vault (USD, 6 decimals), NAV after the last deposit or withdrawal
     pub tvl: u64,
     
     /// Accumulated fees collected
@@ -35,6 +37,139 @@ pub struct StrategyVault {
     
     /// PDA bump seed
     pub bump: u8,
+    
+    /// SPL mint of the vault's share ("ETF") token, mint authority is this PDA
+    pub share_mint: Pubkey,
+    
+    /// Pyth price account for each token in the composition, aligned with target_weights
+    pub price_feeds: [Pubkey; 10],
+    
+    /// Mint of each token in the composition, aligned with target_weights
+    pub mints: [Pubkey; 10],
+    
+    /// Vault token account holding each composition mint, aligned with mints
+    pub vault_token_accounts: [Pubkey; 10],
+    
+    /// Whether deposits require a DepositorPass issued by the owner or attestor
+    pub is_private: bool,
+    
+    /// Annual management fee in basis points, accrued to the curator
+    pub management_fee_bps: u16,
+    
+    /// Timestamp up to which the management fee has been accrued
+    pub last_fee_accrual: i64,
+    
+    /// Fee shares accrued to the curator, not yet minted
+    pub curator_fee_shares: u64,
+    
+    /// Performance fee in basis points of gains above the high-water mark
+    pub performance_fee_bps: u16,
+    
+    /// Minimum seconds between performance fee crystallisations on deposit
+    pub performance_fee_interval: i64,
+    
+    /// Highest NAV per share on which performance fees were charged (scaled 1e9)
+    pub high_water_mark: u64,
+    
+    /// Last performance fee crystallisation timestamp
+    pub last_crystallization: i64,
+    
+    /// Accumulated performance fee shares charged
+    pub performance_fees_collected: u64,
+    
+    /// Protocol swap fees held in each vault token account, aligned with mints
+    pub protocol_swap_fees: [u64; 10],
+    
+    /// Fee on deposits in basis points of the shares minted
+    pub entry_fee_bps: u16,
+    
+    /// Fee on withdrawals in basis points of the shares redeemed
+    pub exit_fee_bps: u16,
+    
+    /// Fee shares accrued to the protocol, not yet minted
+    pub protocol_fee_shares: u64,
+    
+    /// Curator's part of the swap fees held in each vault token account, aligned with mints
+    pub curator_swap_fees: [u64; 10],
+    
+    /// Referrer's cut of the fees left after the protocol's share, in basis points
+    pub referrer_fee_share_bps: u16,
+    
+    /// Fee shares accrued to all referrers, not yet minted
+    pub referrer_fee_shares: u64,
+    
+    /// The wallet that created this strategy, used in the PDA seeds; never changes
+    pub creator: Pubkey,
+    
+    /// Proposed new owner awaiting acceptance, default if none
+    pub pending_owner: Pubkey,
+    
+    /// Key allowed to rebalance on the owner's behalf, default if none
+    pub rebalancer: Pubkey,
+    
+    /// Seconds between proposing and executing a composition change
+    pub weights_timelock: i64,
+    
+    /// Earliest execution time of the pending composition, 0 if none
+    pub pending_weights_eta: i64,
+    
+    /// Number of tokens in the pending composition
+    pub pending_num_tokens: u8,
+    
+    /// Pending target weights in basis points
+    pub pending_target_weights: [u16; 10],
+    
+    /// Pending mint for each weight
+    pub pending_mints: [Pubkey; 10],
+    
+    /// Pending Pyth price account for each weight
+    pub pending_price_feeds: [Pubkey; 10],
+    
+    /// Earliest execution time of an announced off-target rebalance, 0 if none
+    pub pending_rebalance_eta: i64,
+    
+    /// Input mint of the announced off-target rebalance
+    pub pending_rebalance_mint_in: Pubkey,
+    
+    /// Output mint of the announced off-target rebalance
+    pub pending_rebalance_mint_out: Pubkey,
+    
+    /// Maximum input amount of the announced off-target rebalance
+    pub pending_rebalance_max_in: u64,
+    
+    /// Set by the protocol guardian; the strategy stays withdraw-only until
+    /// the protocol admin unfreezes it
+    pub frozen: bool,
+    
+    /// Key allowed to issue and revoke DepositorPasses alongside the owner,
+    /// such as a KYC provider; default if none
+    pub attestor: Pubkey,
+    
+    /// Share of the share supply that must vote for a proposal to be valid
+    pub governance_quorum_bps: u16,
+    
+    /// Share of votes cast that must approve a proposal for it to pass
+    pub governance_threshold_bps: u16,
+    
+    /// Seconds a proposal is open for voting; 0 while governance is disabled
+    pub governance_voting_period: i64,
+    
+    /// Number of proposals created, used to derive the next proposal PDA
+    pub proposal_count: u64,
+    
+    /// Whether the pending composition was approved by a shareholder vote;
+    /// anyone can then execute it and the owner can't cancel it
+    pub pending_weights_governed: bool,
+    
+    /// Fee rates of the pending fee increase
+    pub pending_fees: StrategyFeeParams,
+    
+    /// Earliest execution time of the pending fee increase, 0 if none
+    pub pending_fees_eta: i64,
+    
+    /// Whether the pending fee increase was approved by a shareholder vote;
+    /// anyone can then execute it and the owner can't cancel it
+    pub pending_fees_governed: bool,
 }
 
 impl StrategyVault {
@@ -44,15 +179,460 @@ impl StrategyVault {
         + 1   // strategy_type
         + 20  // target_weights (10 * 2 bytes)
         + 1   // num_tokens
-        + 1   // is_active
+        + 1   // status
         + 8   // tvl
         + 8   // fees_collected
         + 8   // last_rebalance
-        + 1;  // bump
+        + 1   // bump
+        + 32  // share_mint
+        + 320  // price_feeds (10 * 32 bytes)
+        + 320  // mints (10 * 32 bytes)
+        + 320  // vault_token_accounts (10 * 32 bytes)
+        + 1    // is_private
+        + 2    // management_fee_bps
+        + 8    // last_fee_accrual
+        + 8    // curator_fee_shares
+        + 2    // performance_fee_bps
+        + 8    // performance_fee_interval
+        + 8    // high_water_mark
+        + 8    // last_crystallization
+        + 8    // performance_fees_collected
+        + 80   // protocol_swap_fees (10 * 8 bytes)
+        + 2    // entry_fee_bps
+        + 2    // exit_fee_bps
+        + 8    // protocol_fee_shares
+        + 80   // curator_swap_fees (10 * 8 bytes)
+        + 2    // referrer_fee_share_bps
+        + 8    // referrer_fee_shares
+        + 32   // creator
+        + 32   // pending_owner
+        + 32   // rebalancer
+        + 8    // weights_timelock
+        + 8    // pending_weights_eta
+        + 1    // pending_num_tokens
+        + 20   // pending_target_weights (10 * 2 bytes)
+        + 320  // pending_mints (10 * 32 bytes)
+        + 320  // pending_price_feeds (10 * 32 bytes)
+        + 8    // pending_rebalance_eta
+        + 32   // pending_rebalance_mint_in
+        + 32   // pending_rebalance_mint_out
+        + 8    // pending_rebalance_max_in
+        + 1    // frozen
+        + 32   // attestor
+        + 2    // governance_quorum_bps
+        + 2    // governance_threshold_bps
+        + 8    // governance_voting_period
+        + 8    // proposal_count
+        + 1    // pending_weights_governed
+        + StrategyFeeParams::LEN  // pending_fees
+        + 8    // pending_fees_eta
+        + 1;   // pending_fees_governed
+    
+    /// Maximum number of tokens in a composition
+    pub const MAX_TOKENS: usize = 10;
+    
+    /// Decimals of the share mint created at initialization
+    pub const SHARE_DECIMALS: u8 = 6;
+    
+    /// Shortest allowed composition change timelock (1 day)
+    pub const MIN_WEIGHTS_TIMELOCK: i64 = 24 * 60 * 60;
+    
+    /// Shortest allowed governance voting period (1 day)
+    pub const MIN_VOTING_PERIOD: i64 = 24 * 60 * 60;
+    
+    /// Check a composition: 1 to MAX_TOKENS distinct mints, one price feed
+    /// per mint, and weights summing to 10000 bps.
+    pub fn validate_composition(
+        target_weights: &[u16],
+        mints: &[Pubkey],
+        price_feeds: &[Pubkey],
+    ) -> Result<()> {
+        require!(
+            !target_weights.is_empty() && target_weights.len() <= Self::MAX_TOKENS,
+            KagemushaError::InvalidTokenCount
+        );
+        require!(
+            mints.len() == target_weights.len(),
+            KagemushaError::InvalidTokenCount
+        );
+        require!(
+            price_feeds.len() == target_weights.len(),
+            KagemushaError::InvalidPriceFeed
+        );
+        for (i, mint) in mints.iter().enumerate() {
+            require!(*mint != Pubkey::default(), KagemushaError::MintMismatch);
+            require!(!mints[..i].contains(mint), KagemushaError::DuplicateMint);
+        }
+        
+        let weight_sum: u32 = target_weights.iter().map(|&w| w as u32).sum();
+        require!(weight_sum == 10000, KagemushaError::InvalidWeightSum);
+        Ok(())
+    }
     
     pub fn name_as_str(&self) -> String {
+        String::from_utf8_lossy(self.name_seed()).to_string()
+    }
+    
+    /// Total shares for pricing: minted supply plus accrued, unminted fee shares
+    pub fn total_shares(&self, minted_supply: u64) -> Result<u64> {
+        minted_supply
+            .checked_add(self.curator_fee_shares)
+            .and_then(|total| total.checked_add(self.protocol_fee_shares))
+            .and_then(|total| total.checked_add(self.referrer_fee_shares))
+            .ok_or_else(|| error!(KagemushaError::MathOverflow))
+    }
+    
+    /// Split `fee_shares` with the protocol, and the referrer if `referred`,
+    /// and credit each part as unminted fee shares.
+    fn credit_fee_shares(
+        &mut self,
+        fee_shares: u64,
+        config: &ProtocolConfig,
+        referred: bool,
+    ) -> Result<FeeSplit> {
+        let referrer_share_bps = if referred { self.referrer_fee_share_bps } else { 0 };
+        let split = fees::split_fee(fee_shares, config.protocol_fee_share_bps, referrer_share_bps)?;
+        self.protocol_fee_shares = self
+            .protocol_fee_shares
+            .checked_add(split.protocol)
+            .ok_or(KagemushaError::MathOverflow)?;
+        self.curator_fee_shares = self
+            .curator_fee_shares
+            .checked_add(split.curator)
+            .ok_or(KagemushaError::MathOverflow)?;
+        self.referrer_fee_shares = self
+            .referrer_fee_shares
+            .checked_add(split.referrer)
+            .ok_or(KagemushaError::MathOverflow)?;
+        Ok(split)
+    }
+    
+    /// Accrue the management fee since the last accrual as fee shares.
+    /// Must run before any instruction prices or changes shares.
+    pub fn accrue_management_fee(
+        &mut self,
+        minted_supply: u64,
+        now: i64,
+        config: &ProtocolConfig,
+    ) -> Result<FeeSplit> {
+        let elapsed = now.saturating_sub(self.last_fee_accrual);
+        let total_shares = self.total_shares(minted_supply)?;
+        let fee_shares = fees::management_fee_shares(total_shares, self.management_fee_bps, elapsed)?;
+        
+        // Keep accruing from the old timestamp while the fee rounds to zero
+        if fee_shares > 0 || total_shares == 0 || self.management_fee_bps == 0 {
+            self.last_fee_accrual = now;
+        }
+        self.credit_fee_shares(fee_shares, config, false)
+    }
+    
+    /// Crystallise the performance fee against `nav` as fee shares.
+    /// Unless `force` is set, only runs once `performance_fee_interval`
+    /// has passed since the last crystallisation.
+    pub fn crystallize_performance_fee(
+        &mut self,
+        nav: u64,
+        minted_supply: u64,
+        now: i64,
+        force: bool,
+        config: &ProtocolConfig,
+    ) -> Result<FeeSplit> {
+        if !force && now.saturating_sub(self.last_crystallization) < self.performance_fee_interval {
+            return Ok(FeeSplit::default());
+        }
+        let (fee_shares, high_water_mark) = fees::performance_fee_shares(
+            nav,
+            self.total_shares(minted_supply)?,
+            self.high_water_mark,
+            self.performance_fee_bps,
+        )?;
+        
+        self.high_water_mark = high_water_mark;
+        self.last_crystallization = now;
+        self.performance_fees_collected = self
+            .performance_fees_collected
+            .checked_add(fee_shares)
+            .ok_or(KagemushaError::MathOverflow)?;
+        self.credit_fee_shares(fee_shares, config, false)
+    }
+    
+    /// Split `shares` into (shares kept by the holder, fee split) and credit
+    /// the fee shares to their recipients. The referrer's part must also be
+    /// credited to the position's ReferrerAccount.
+    pub fn charge_share_fee(
+        &mut self,
+        shares: u64,
+        fee_bps: u16,
+        config: &ProtocolConfig,
+        referred: bool,
+    ) -> Result<(u64, FeeSplit)> {
+        let fee_shares = fees::bps_of(shares, fee_bps)?;
+        let split = self.credit_fee_shares(fee_shares, config, referred)?;
+        Ok((shares - fee_shares, split))
+    }
+    
+    /// Reserve a swap fee of `fee` tokens in basket slot `index`, split
+    /// between the protocol and the curator.
+    pub fn reserve_swap_fee(&mut self, index: usize, fee: u64, config: &ProtocolConfig) -> Result<FeeSplit> {
+        let split = fees::split_fee(fee, config.protocol_fee_share_bps, 0)?;
+        self.protocol_swap_fees[index] = self.protocol_swap_fees[index]
+            .checked_add(split.protocol)
+            .ok_or(KagemushaError::MathOverflow)?;
+        self.curator_swap_fees[index] = self.curator_swap_fees[index]
+            .checked_add(split.curator)
+            .ok_or(KagemushaError::MathOverflow)?;
+        self.fees_collected = self
+            .fees_collected
+            .checked_add(fee)
+            .ok_or(KagemushaError::MathOverflow)?;
+        Ok(split)
+    }
+    
+    /// Fee tokens held in basket slot `index` that belong to fee recipients,
+    /// not depositors
+    pub fn reserved_fees(&self, index: usize) -> Result<u64> {
+        self.protocol_swap_fees[index]
+            .checked_add(self.curator_swap_fees[index])
+            .ok_or_else(|| error!(KagemushaError::MathOverflow))
+    }
+    
+    /// Whether `authority` may sign rebalance instructions
+    pub fn can_rebalance(&self, authority: &Pubkey) -> bool {
+        *authority == self.owner
+            || (self.rebalancer != Pubkey::default() && *authority == self.rebalancer)
+    }
+    
+    /// Whether weight updates, fee increases and new rebalancers need a
+    /// passed shareholder proposal
+    pub fn governance_enabled(&self) -> bool {
+        self.governance_voting_period > 0
+    }
+    
+    /// Stage a validated composition as the pending one, executable after the
+    /// weights timelock. `governed` marks a composition passed by a
+    /// shareholder vote. Returns its execution time.
+    pub fn stage_weights(
+        &mut self,
+        target_weights: &[u16],
+        mints: &[Pubkey],
+        price_feeds: &[Pubkey],
+        governed: bool,
+        now: i64,
+    ) -> Result<i64> {
+        let eta = now
+            .checked_add(self.weights_timelock)
+            .ok_or(KagemushaError::MathOverflow)?;
+        
+        let mut weights = [0u16; 10];
+        weights[..target_weights.len()].copy_from_slice(target_weights);
+        let mut basket_mints = [Pubkey::default(); 10];
+        basket_mints[..mints.len()].copy_from_slice(mints);
+        let mut feeds = [Pubkey::default(); 10];
+        feeds[..price_feeds.len()].copy_from_slice(price_feeds);
+        
+        self.pending_num_tokens = target_weights.len() as u8;
+        self.pending_target_weights = weights;
+        self.pending_mints = basket_mints;
+        self.pending_price_feeds = feeds;
+        self.pending_weights_eta = eta;
+        self.pending_weights_governed = governed;
+        Ok(eta)
+    }
+    
+    /// Whether `authority` may issue and revoke DepositorPasses
+    pub fn can_attest(&self, authority: &Pubkey) -> bool {
+        *authority == self.owner
+            || (self.attestor != Pubkey::default() && *authority == self.attestor)
+    }
+    
+    /// Index of `mint` in the composition, if it is a basket asset
+    pub fn basket_index(&self, mint: &Pubkey) -> Option<usize> {
+        self.mints[..self.num_tokens as usize]
+            .iter()
+            .position(|m| m == mint)
+    }
+    
+    /// Whether an announced composition change, off-target rebalance or fee
+    /// increase has not reached its effective time yet. Until then
+    /// depositors can exit without exit fees.
+    pub fn in_exit_window(&self, now: i64) -> bool {
+        (self.pending_weights_eta != 0 && now < self.pending_weights_eta)
+            || (self.pending_rebalance_eta != 0 && now < self.pending_rebalance_eta)
+            || (self.pending_fees_eta != 0 && now < self.pending_fees_eta)
+    }
+    
+    /// Exit fee charged on withdrawals at `now`, waived during an exit window
+    pub fn effective_exit_fee_bps(&self, now: i64) -> u16 {
+        if self.in_exit_window(now) {
+            0
+        } else {
+            self.exit_fee_bps
+        }
+    }
+    
+    /// Consume the announced off-target rebalance for a swap of `amount_in`
+    /// from `mint_in` to `mint_out`, failing unless it matches and is due.
+    pub fn take_announced_rebalance(
+        &mut self,
+        mint_in: &Pubkey,
+        mint_out: &Pubkey,
+        amount_in: u64,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            self.pending_rebalance_eta != 0
+                && self.pending_rebalance_mint_in == *mint_in
+                && self.pending_rebalance_mint_out == *mint_out
+                && amount_in <= self.pending_rebalance_max_in,
+            KagemushaError::RebalanceNotAnnounced
+        );
+        require!(now >= self.pending_rebalance_eta, KagemushaError::TimelockNotElapsed);
+        self.clear_pending_rebalance();
+        Ok(())
+    }
+    
+    pub fn clear_pending_rebalance(&mut self) {
+        self.pending_rebalance_eta = 0;
+        self.pending_rebalance_mint_in = Pubkey::default();
+        self.pending_rebalance_mint_out = Pubkey::default();
+        self.pending_rebalance_max_in = 0;
+    }
+    
+    /// Index of `mint` in the pending composition, if a change is pending
+    pub fn pending_index(&self, mint: &Pubkey) -> Option<usize> {
+        if self.pending_weights_eta == 0 {
+            return None;
+        }
+        self.pending_mints[..self.pending_num_tokens as usize]
+            .iter()
+            .position(|m| m == mint)
+    }
+    
+    /// Recorded vault token account for a basket mint
+    pub fn vault_account_for(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.basket_index(mint).map(|i| self.vault_token_accounts[i])
+    }
+    
+    /// Name bytes as used in the PDA seeds (without zero padding)
+    pub fn name_seed(&self) -> &[u8] {
         let end = self.name.iter().position(|&c| c == 0).unwrap_or(32);
-        String::from_utf8_lossy(&self.name[..end]).to_string()
+        &self.name[..end]
+    }
+}
+
+/// Lifecycle status of a StrategyVault. Withdrawals are allowed in every
+/// status.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StrategyStatus {
+    /// Deposits, withdrawals and rebalances are open
+    #[default]
+    Active,
+    /// New deposits are refused; rebalances continue
+    DepositsPaused,
+    /// Only withdrawals are allowed
+    WithdrawOnly,
+    /// Permanently wound down; only withdrawals are allowed
+    Shutdown,
+}
+
+impl StrategyStatus {
+    pub fn accepts_deposits(&self) -> bool {
+        *self == Self::Active
+    }
+    
+    pub fn allows_rebalance(&self) -> bool {
+        matches!(self, Self::Active | Self::DepositsPaused)
+    }
+}
+
+/// Settable fee rates of a StrategyVault.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct StrategyFeeParams {
+    pub management_fee_bps: u16,
+    pub performance_fee_bps: u16,
+    pub performance_fee_interval: i64,
+    pub entry_fee_bps: u16,
+    pub exit_fee_bps: u16,
+    pub referrer_fee_share_bps: u16,
+}
+
+impl StrategyFeeParams {
+    pub const LEN: usize = 2  // management_fee_bps
+        + 2   // performance_fee_bps
+        + 8   // performance_fee_interval
+        + 2   // entry_fee_bps
+        + 2   // exit_fee_bps
+        + 2;  // referrer_fee_share_bps
+    
+    /// Check the rates against the program and protocol fee caps
+    pub fn validate(&self, config: &ProtocolConfig) -> Result<()> {
+        require!(
+            self.management_fee_bps <= ProtocolConfig::MAX_FEE_BPS,
+            KagemushaError::FeeTooHigh
+        );
+        require!(
+            self.performance_fee_bps <= ProtocolConfig::MAX_PERFORMANCE_FEE_BPS,
+            KagemushaError::FeeTooHigh
+        );
+        require!(self.performance_fee_interval >= 0, KagemushaError::FeeTooHigh);
+        require!(
+            self.entry_fee_bps <= config.max_entry_exit_fee_bps
+                && self.exit_fee_bps <= config.max_entry_exit_fee_bps,
+            KagemushaError::FeeTooHigh
+        );
+        require!(
+            self.referrer_fee_share_bps as u64 <= fees::BPS_DENOMINATOR,
+            KagemushaError::FeeTooHigh
+        );
+        Ok(())
+    }
+}
+
+impl StrategyVault {
+    pub fn apply_fees(&mut self, params: &StrategyFeeParams, config: &ProtocolConfig) -> Result<()> {
+        params.validate(config)?;
+        self.management_fee_bps = params.management_fee_bps;
+        self.performance_fee_bps = params.performance_fee_bps;
+        self.performance_fee_interval = params.performance_fee_interval;
+        self.entry_fee_bps = params.entry_fee_bps;
+        self.exit_fee_bps = params.exit_fee_bps;
+        self.referrer_fee_share_bps = params.referrer_fee_share_bps;
+        Ok(())
+    }
+    
+    /// Whether `params` raise any fee rate charged to depositors
+    pub fn raises_fees(&self, params: &StrategyFeeParams) -> bool {
+        params.management_fee_bps > self.management_fee_bps
+            || params.performance_fee_bps > self.performance_fee_bps
+            || params.performance_fee_interval < self.performance_fee_interval
+            || params.entry_fee_bps > self.entry_fee_bps
+            || params.exit_fee_bps > self.exit_fee_bps
+    }
+    
+    /// Stage validated fee rates that raise a fee as the pending fee change,
+    /// executable after the weights timelock. `governed` marks rates passed
+    /// by a shareholder vote. Returns its execution time.
+    pub fn stage_fees(
+        &mut self,
+        params: &StrategyFeeParams,
+        governed: bool,
+        now: i64,
+        config: &ProtocolConfig,
+    ) -> Result<i64> {
+        params.validate(config)?;
+        let eta = now
+            .checked_add(self.weights_timelock)
+            .ok_or(KagemushaError::MathOverflow)?;
+        self.pending_fees = params.clone();
+        self.pending_fees_eta = eta;
+        self.pending_fees_governed = governed;
+        Ok(eta)
+    }
+    
+    pub fn clear_pending_fees(&mut self) {
+        self.pending_fees = StrategyFeeParams::default();
+        self.pending_fees_eta = 0;
+        self.pending_fees_governed = false;
     }
 }
 
@@ -65,10 +645,10 @@ pub struct UserPosition {
     /// The user who owns this position
     pub user: Pubkey,
     
-    /// Amount of LP tokens representing share ownership
+    /// Amount of share tokens minted to this user through deposits
     pub lp_shares: u64,
     
-    /// Timestamp of initial deposit
+    /// Timestamp of the latest deposit, the checkpoint for vote weight
     pub deposit_time: i64,
     
     /// Entry value in USDC (for PnL calculation)
@@ -76,6 +656,9 @@ pub struct UserPosition {
     
     /// PDA bump seed
     pub bump: u8,
+    
+    /// Referrer recorded when the position was opened, default if none
+    pub referrer: Pubkey,
 }
 
 impl UserPosition {
@@ -85,5 +668,681 @@ impl UserPosition {
         + 8   // lp_shares
         + 8   // deposit_time
         + 8   // entry_value
+        + 1   // bump
+        + 32; // referrer
+    
+    /// Record `referrer` on a newly opened position. Once the position is
+    /// open its referrer can't change.
+    pub fn set_referrer(&mut self, referrer: Option<Pubkey>, user: &Pubkey) -> Result<()> {
+        let Some(referrer) = referrer else {
+            return Ok(());
+        };
+        if self.vault == Pubkey::default() {
+            require!(referrer != *user, KagemushaError::InvalidReferrer);
+            self.referrer = referrer;
+        } else {
+            require!(referrer == self.referrer, KagemushaError::ReferrerAlreadySet);
+        }
+        Ok(())
+    }
+    
+    /// Whether the position has a referrer, checking that `referrer_account`
+    /// is the referrer's account when it does.
+    pub fn check_referrer(&self, referrer_account: Option<&ReferrerAccount>) -> Result<bool> {
+        if self.referrer == Pubkey::default() {
+            return Ok(false);
+        }
+        require!(
+            referrer_account.is_some_and(|account| account.referrer == self.referrer),
+            KagemushaError::InvalidReferrer
+        );
+        Ok(true)
+    }
+    
+    /// Shares the position held unchanged since before `time`: none if it
+    /// deposited at or after `time`, otherwise its current `lp_shares`, which
+    /// withdrawals only reduce.
+    pub fn shares_held_before(&self, time: i64) -> u64 {
+        if self.deposit_time < time {
+            self.lp_shares
+        } else {
+            0
+        }
+    }
+    
+    /// Reduce the position by `shares` redeemed, releasing the matching
+    /// slice of entry value. Shares beyond `lp_shares` (received by
+    /// transfer) carry no entry value.
+    pub fn redeem(&mut self, shares: u64) -> Result<()> {
+        let redeemed = shares.min(self.lp_shares);
+        if redeemed > 0 {
+            let released = (self.entry_value as u128)
+                .checked_mul(redeemed as u128)
+                .ok_or(KagemushaError::MathOverflow)?
+                / self.lp_shares as u128;
+            self.entry_value = self.entry_value.saturating_sub(released as u64);
+        }
+        self.lp_shares -= redeemed;
+        Ok(())
+    }
+}
+
+/// Referral fee balance of one referrer in one strategy, seeded by
+/// `[b"referrer", strategy, referrer]`.
+#[account]
+pub struct ReferrerAccount {
+    /// The strategy vault the referrer earns fees in
+    pub strategy: Pubkey,
+    
+    /// The referrer's wallet
+    pub referrer: Pubkey,
+    
+    /// Fee shares earned, not yet minted
+    pub pending_shares: u64,
+    
+    /// Total fee shares earned over the account's lifetime
+    pub total_earned: u64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ReferrerAccount {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // strategy
+        + 32  // referrer
+        + 8   // pending_shares
+        + 8   // total_earned
         + 1;  // bump
+    
+    /// Credit the referrer's part of a fee split
+    pub fn credit(&mut self, shares: u64) -> Result<()> {
+        self.pending_shares = self
+            .pending_shares
+            .checked_add(shares)
+            .ok_or(KagemushaError::MathOverflow)?;
+        self.total_earned = self
+            .total_earned
+            .checked_add(shares)
+            .ok_or(KagemushaError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// Scoped rebalance session for one delegate key, seeded by
+/// `[b"delegation", strategy, delegate]`. Notional limits are in USD
+/// (6 decimals), valued at the input mint's oracle price.
+#[account]
+pub struct Delegation {
+    /// The strategy vault the delegate may rebalance
+    pub strategy: Pubkey,
+    
+    /// The delegated session key
+    pub delegate: Pubkey,
+    
+    /// Timestamp after which the delegation can no longer be used
+    pub expires_at: i64,
+    
+    /// Maximum notional of a single rebalance
+    pub max_notional_per_rebalance: u64,
+    
+    /// Maximum cumulative notional per UTC day
+    pub max_daily_notional: u64,
+    
+    /// UTC day (unix time / 86400) that `daily_notional` is counted for
+    pub current_day: i64,
+    
+    /// Notional rebalanced so far in `current_day`
+    pub daily_notional: u64,
+    
+    /// Mints the delegate may swap into
+    pub allowed_output_mints: [Pubkey; 10],
+    
+    /// Number of entries used in allowed_output_mints
+    pub num_allowed_mints: u8,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Delegation {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // strategy
+        + 32  // delegate
+        + 8   // expires_at
+        + 8   // max_notional_per_rebalance
+        + 8   // max_daily_notional
+        + 8   // current_day
+        + 8   // daily_notional
+        + 320 // allowed_output_mints (10 * 32 bytes)
+        + 1   // num_allowed_mints
+        + 1;  // bump
+    
+    pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+    
+    pub fn apply(&mut self, params: &DelegationParams) -> Result<()> {
+        require!(
+            !params.allowed_output_mints.is_empty()
+                && params.allowed_output_mints.len() <= StrategyVault::MAX_TOKENS,
+            KagemushaError::InvalidTokenCount
+        );
+        let mut mints = [Pubkey::default(); 10];
+        mints[..params.allowed_output_mints.len()].copy_from_slice(&params.allowed_output_mints);
+        self.expires_at = params.expires_at;
+        self.max_notional_per_rebalance = params.max_notional_per_rebalance;
+        self.max_daily_notional = params.max_daily_notional;
+        self.allowed_output_mints = mints;
+        self.num_allowed_mints = params.allowed_output_mints.len() as u8;
+        Ok(())
+    }
+    
+    /// Check a rebalance of `notional` into `mint_out` against the
+    /// delegation's limits and count it towards today's total.
+    pub fn debit(&mut self, notional: u64, mint_out: &Pubkey, now: i64) -> Result<()> {
+        require!(now < self.expires_at, KagemushaError::DelegationExpired);
+        require!(
+            self.allowed_output_mints[..self.num_allowed_mints as usize].contains(mint_out),
+            KagemushaError::OutputMintNotAllowed
+        );
+        require!(
+            notional <= self.max_notional_per_rebalance,
+            KagemushaError::DelegationLimitExceeded
+        );
+        
+        let day = now / Self::SECONDS_PER_DAY;
+        if day != self.current_day {
+            self.current_day = day;
+            self.daily_notional = 0;
+        }
+        let spent = self
+            .daily_notional
+            .checked_add(notional)
+            .ok_or(KagemushaError::MathOverflow)?;
+        require!(spent <= self.max_daily_notional, KagemushaError::DelegationLimitExceeded);
+        self.daily_notional = spent;
+        Ok(())
+    }
+}
+
+/// Settable limits of a Delegation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DelegationParams {
+    pub expires_at: i64,
+    pub max_notional_per_rebalance: u64,
+    pub max_daily_notional: u64,
+    pub allowed_output_mints: Vec<Pubkey>,
+}
+
+/// Allowlist entry letting one wallet deposit into a private strategy.
+#[account]
+pub struct DepositorPass {
+    /// The strategy vault this pass grants access to
+    pub strategy: Pubkey,
+    
+    /// The wallet allowed to deposit
+    pub depositor: Pubkey,
+    
+    /// Owner or attestor that issued the pass
+    pub issued_by: Pubkey,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl DepositorPass {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // strategy
+        + 32  // depositor
+        + 32  // issued_by
+        + 1;  // bump
+}
+
+/// Change a governed strategy's shareholders vote on.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ProposalAction {
+    /// Stage a new composition, executable after the weights timelock
+    Weights {
+        target_weights: Vec<u16>,
+        mints: Vec<Pubkey>,
+        price_feeds: Vec<Pubkey>,
+    },
+    /// Replace the strategy's fee rates
+    Fees(StrategyFeeParams),
+    /// Set the delegated rebalancer
+    Rebalancer(Pubkey),
+}
+
+impl ProposalAction {
+    /// Serialized size of the largest action (a full composition)
+    pub const MAX_LEN: usize = 1  // variant
+        + 4 + 2 * StrategyVault::MAX_TOKENS   // target_weights
+        + 4 + 32 * StrategyVault::MAX_TOKENS  // mints
+        + 4 + 32 * StrategyVault::MAX_TOKENS; // price_feeds
+}
+
+/// Lifecycle of a governance proposal.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProposalStatus {
+    /// Open for votes, or awaiting execution once voting ends
+    #[default]
+    Voting,
+    /// Passed and applied to the strategy
+    Executed,
+    /// Withdrawn by the strategy owner
+    Cancelled,
+}
+
+/// A change to a governed strategy put to its shareholders, seeded by
+/// `[b"proposal", strategy, id]`.
+#[account]
+pub struct Proposal {
+    /// The strategy vault the proposal changes
+    pub strategy: Pubkey,
+    
+    /// Sequence number within the strategy
+    pub id: u64,
+    
+    /// Strategy owner that created the proposal
+    pub proposer: Pubkey,
+    
+    /// Change applied if the proposal passes
+    pub action: ProposalAction,
+    
+    /// Creation time
+    pub created_at: i64,
+    
+    /// End of the voting period
+    pub voting_ends_at: i64,
+    
+    /// Share supply at creation, the base for the quorum
+    pub snapshot_supply: u64,
+    
+    /// Shares voting in favour
+    pub votes_for: u64,
+    
+    /// Shares voting against
+    pub votes_against: u64,
+    
+    /// Lifecycle status
+    pub status: ProposalStatus,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // strategy
+        + 8   // id
+        + 32  // proposer
+        + ProposalAction::MAX_LEN  // action
+        + 8   // created_at
+        + 8   // voting_ends_at
+        + 8   // snapshot_supply
+        + 8   // votes_for
+        + 8   // votes_against
+        + 1   // status
+        + 1;  // bump
+    
+    /// Whether the votes cast reach the quorum and more than `threshold_bps`
+    /// of them approve
+    pub fn passed(&self, quorum_bps: u16, threshold_bps: u16) -> Result<bool> {
+        let votes_cast = self
+            .votes_for
+            .checked_add(self.votes_against)
+            .ok_or(KagemushaError::MathOverflow)?;
+        if votes_cast == 0 || votes_cast < fees::bps_of(self.snapshot_supply, quorum_bps)? {
+            return Ok(false);
+        }
+        Ok(self.votes_for > fees::bps_of(votes_cast, threshold_bps)?)
+    }
+}
+
+/// One shareholder's vote on a proposal, seeded by
+/// `[b"vote", proposal, voter]`. Its existence prevents voting twice, and it
+/// owns the `[b"vote_escrow", vote_record]` token account locking the
+/// voting shares until voting ends.
+#[account]
+pub struct VoteRecord {
+    /// The proposal voted on
+    pub proposal: Pubkey,
+    
+    /// The voting shareholder
+    pub voter: Pubkey,
+    
+    /// Shares locked and counted for the vote
+    pub weight: u64,
+    
+    /// Whether the vote approves the proposal
+    pub approve: bool,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // proposal
+        + 32  // voter
+        + 8   // weight
+        + 1   // approve
+        + 1;  // bump
+}
+
+/// Protocol approval of one basket mint, seeded by `[b"mint_registry", mint]`
+/// and maintained by the protocol admin.
+#[account]
+pub struct MintRegistry {
+    /// The approved mint
+    pub mint: Pubkey,
+    
+    /// Pyth price account strategies must use for this mint
+    pub oracle: Pubkey,
+    
+    /// Decimals of the mint
+    pub decimals: u8,
+    
+    /// Token program that owns the mint
+    pub token_program: Pubkey,
+    
+    /// Risk tier assigned by the protocol (0 = lowest risk)
+    pub risk_tier: u8,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl MintRegistry {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // mint
+        + 32  // oracle
+        + 1   // decimals
+        + 32  // token_program
+        + 1   // risk_tier
+        + 1;  // bump
+    
+    /// Check that `info` is the registry entry approving `mint` with
+    /// `price_feed` as its oracle.
+    pub fn verify(info: &AccountInfo, mint: &Pubkey, price_feed: &Pubkey) -> Result<()> {
+        require!(info.owner == &crate::ID, KagemushaError::MintNotApproved);
+        let entry = Self::try_deserialize(&mut &info.data.borrow()[..])?;
+        require!(entry.mint == *mint, KagemushaError::MintNotApproved);
+        require!(entry.oracle == *price_feed, KagemushaError::InvalidPriceFeed);
+        Ok(())
+    }
+    
+    /// Check one registry entry per composition mint, in order, at the front
+    /// of `accounts`.
+    pub fn verify_composition(
+        accounts: &[AccountInfo],
+        mints: &[Pubkey],
+        price_feeds: &[Pubkey],
+    ) -> Result<()> {
+        require!(accounts.len() >= mints.len(), KagemushaError::MintNotApproved);
+        for ((info, mint), price_feed) in accounts.iter().zip(mints).zip(price_feeds) {
+            Self::verify(info, mint, price_feed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Global protocol settings, a singleton PDA seeded by `b"protocol_config"`.
+#[account]
+pub struct ProtocolConfig {
+    /// Authority allowed to update this config
+    pub admin: Pubkey,
+    
+    /// Wallet that owns the protocol fee token accounts
+    pub treasury: Pubkey,
+    
+    /// Emergency key that can freeze strategies and revoke rebalancers, but never move funds
+    pub guardian: Pubkey,
+    
+    /// Protocol fee on rebalance swaps in basis points
+    pub protocol_fee_bps: u16,
+    
+    /// Protocol's share of every collected fee in basis points, the rest goes to the curator
+    pub protocol_fee_share_bps: u16,
+    
+    /// Jupiter program used for rebalance swaps
+    pub jupiter_program: Pubkey,
+    
+    /// Pyth oracle program that must own every price account, which differs
+    /// between clusters
+    pub pyth_program: Pubkey,
+    
+    /// Maximum entry and exit fee a strategy may charge, in basis points
+    pub max_entry_exit_fee_bps: u16,
+    
+    /// Whether deposits are paused protocol-wide
+    pub deposits_paused: bool,
+    
+    /// Whether rebalances are paused protocol-wide
+    pub rebalances_paused: bool,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // admin
+        + 32  // treasury
+        + 32  // guardian
+        + 2   // protocol_fee_bps
+        + 2   // protocol_fee_share_bps
+        + 32  // jupiter_program
+        + 32  // pyth_program
+        + 2   // max_entry_exit_fee_bps
+        + 1   // deposits_paused
+        + 1   // rebalances_paused
+        + 1;  // bump
+    
+    /// Upper bound for any fee rate in basis points (10%)
+    pub const MAX_FEE_BPS: u16 = 1000;
+    
+    /// Upper bound for performance fees in basis points (30%)
+    pub const MAX_PERFORMANCE_FEE_BPS: u16 = 3000;
+    
+    /// Anchor event authority PDA of the configured Jupiter program
+    pub fn jupiter_event_authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"__event_authority"], &self.jupiter_program).0
+    }
+}
+
+/// Settable fields of the ProtocolConfig.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProtocolConfigParams {
+    pub treasury: Pubkey,
+    pub guardian: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub protocol_fee_share_bps: u16,
+    pub jupiter_program: Pubkey,
+    pub pyth_program: Pubkey,
+    pub max_entry_exit_fee_bps: u16,
+    pub deposits_paused: bool,
+    pub rebalances_paused: bool,
+}
+
+impl ProtocolConfig {
+    pub fn apply(&mut self, params: &ProtocolConfigParams) -> Result<()> {
+        require!(
+            params.protocol_fee_bps <= Self::MAX_FEE_BPS
+                && params.max_entry_exit_fee_bps <= Self::MAX_FEE_BPS,
+            KagemushaError::FeeTooHigh
+        );
+        require!(
+            params.protocol_fee_share_bps as u64 <= fees::BPS_DENOMINATOR,
+            KagemushaError::FeeTooHigh
+        );
+        require!(params.guardian != self.admin, KagemushaError::InvalidGuardian);
+        self.treasury = params.treasury;
+        self.guardian = params.guardian;
+        self.protocol_fee_bps = params.protocol_fee_bps;
+        self.protocol_fee_share_bps = params.protocol_fee_share_bps;
+        self.jupiter_program = params.jupiter_program;
+        self.pyth_program = params.pyth_program;
+        self.max_entry_exit_fee_bps = params.max_entry_exit_fee_bps;
+        self.deposits_paused = params.deposits_paused;
+        self.rebalances_paused = params.rebalances_paused;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = Delegation::SECONDS_PER_DAY;
+    const NOW: i64 = 100 * DAY + 3_600;
+
+    fn delegation(mint_out: Pubkey) -> Delegation {
+        let mut allowed_output_mints = [Pubkey::default(); 10];
+        allowed_output_mints[0] = mint_out;
+        Delegation {
+            strategy: Pubkey::new_unique(),
+            delegate: Pubkey::new_unique(),
+            expires_at: NOW + 30 * DAY,
+            max_notional_per_rebalance: 1_000_000_000,
+            max_daily_notional: 2_500_000_000,
+            current_day: 0,
+            daily_notional: 0,
+            allowed_output_mints,
+            num_allowed_mints: 1,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn debit_counts_towards_the_current_day() {
+        let mint = Pubkey::new_unique();
+        let mut delegation = delegation(mint);
+        delegation.debit(400_000_000, &mint, NOW).unwrap();
+        delegation.debit(600_000_000, &mint, NOW + 60).unwrap();
+        assert_eq!(delegation.current_day, NOW / DAY);
+        assert_eq!(delegation.daily_notional, 1_000_000_000);
+    }
+
+    #[test]
+    fn debit_enforces_per_rebalance_cap() {
+        let mint = Pubkey::new_unique();
+        let mut delegation = delegation(mint);
+        delegation.debit(1_000_000_000, &mint, NOW).unwrap();
+        assert!(delegation.debit(1_000_000_001, &mint, NOW).is_err());
+        assert_eq!(delegation.daily_notional, 1_000_000_000);
+    }
+
+    #[test]
+    fn debit_enforces_daily_cap() {
+        let mint = Pubkey::new_unique();
+        let mut delegation = delegation(mint);
+        delegation.debit(1_000_000_000, &mint, NOW).unwrap();
+        delegation.debit(1_000_000_000, &mint, NOW).unwrap();
+        assert!(delegation.debit(500_000_001, &mint, NOW).is_err());
+        delegation.debit(500_000_000, &mint, NOW).unwrap();
+        assert_eq!(delegation.daily_notional, 2_500_000_000);
+    }
+
+    #[test]
+    fn debit_resets_daily_total_on_a_new_day() {
+        let mint = Pubkey::new_unique();
+        let mut delegation = delegation(mint);
+        delegation.debit(1_000_000_000, &mint, NOW).unwrap();
+        delegation.debit(1_000_000_000, &mint, NOW).unwrap();
+
+        // Last second of the same UTC day still counts against it
+        let end_of_day = (NOW / DAY + 1) * DAY - 1;
+        assert!(delegation.debit(1_000_000_000, &mint, end_of_day).is_err());
+
+        delegation.debit(1_000_000_000, &mint, end_of_day + 1).unwrap();
+        assert_eq!(delegation.current_day, NOW / DAY + 1);
+        assert_eq!(delegation.daily_notional, 1_000_000_000);
+    }
+
+    #[test]
+    fn debit_rejects_expired_delegation() {
+        let mint = Pubkey::new_unique();
+        let mut delegation = delegation(mint);
+        delegation.debit(1, &mint, delegation.expires_at - 1).unwrap();
+        assert!(delegation.debit(1, &mint, delegation.expires_at).is_err());
+    }
+
+    #[test]
+    fn debit_rejects_output_mint_outside_allowlist() {
+        let mint = Pubkey::new_unique();
+        let mut delegation = delegation(mint);
+        assert!(delegation.debit(1, &Pubkey::new_unique(), NOW).is_err());
+        // Unused slots hold the default key, which must not count as allowed
+        assert!(delegation.debit(1, &Pubkey::default(), NOW).is_err());
+        assert_eq!(delegation.daily_notional, 0);
+    }
+
+    const SUPPLY: u64 = 1_000_000_000;
+
+    fn proposal(votes_for: u64, votes_against: u64) -> Proposal {
+        Proposal {
+            strategy: Pubkey::new_unique(),
+            id: 0,
+            proposer: Pubkey::new_unique(),
+            action: ProposalAction::Rebalancer(Pubkey::new_unique()),
+            created_at: NOW,
+            voting_ends_at: NOW + StrategyVault::MIN_VOTING_PERIOD,
+            snapshot_supply: SUPPLY,
+            votes_for,
+            votes_against,
+            status: ProposalStatus::Voting,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn proposal_needs_quorum_of_snapshot_supply() {
+        // 20% quorum of 1000 shares is 200 shares, counting votes on both sides
+        assert!(proposal(150_000_000, 50_000_000).passed(2000, 5000).unwrap());
+        assert!(!proposal(150_000_000, 49_999_999).passed(2000, 5000).unwrap());
+        assert!(!proposal(199_999_999, 0).passed(2000, 5000).unwrap());
+    }
+
+    #[test]
+    fn proposal_needs_more_than_threshold_of_votes_cast() {
+        // A tie doesn't pass a simple-majority threshold
+        assert!(!proposal(300_000_000, 300_000_000).passed(2000, 5000).unwrap());
+        assert!(proposal(300_000_001, 300_000_000).passed(2000, 5000).unwrap());
+
+        // Exactly two thirds doesn't pass a 6667 bps threshold
+        assert!(!proposal(400_020_000, 199_980_000).passed(2000, 6667).unwrap());
+        assert!(proposal(400_020_001, 199_980_000).passed(2000, 6667).unwrap());
+    }
+
+    #[test]
+    fn proposal_without_votes_fails() {
+        assert!(!proposal(0, 0).passed(2000, 5000).unwrap());
+
+        // Even with an empty snapshot, where the quorum rounds to zero
+        let mut empty = proposal(0, 0);
+        empty.snapshot_supply = 0;
+        assert!(!empty.passed(1, 5000).unwrap());
+    }
+
+    #[test]
+    fn position_votes_only_with_shares_deposited_before_snapshot() {
+        let mut position = UserPosition {
+            vault: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            lp_shares: 500_000_000,
+            deposit_time: NOW - 1,
+            entry_value: 500_000_000,
+            bump: 255,
+            referrer: Pubkey::default(),
+        };
+        assert_eq!(position.shares_held_before(NOW), 500_000_000);
+
+        // Withdrawals after the snapshot only lower the weight
+        position.redeem(200_000_000).unwrap();
+        assert_eq!(position.shares_held_before(NOW), 300_000_000);
+
+        // A deposit at or after the snapshot forfeits the vote
+        position.deposit_time = NOW;
+        assert_eq!(position.shares_held_before(NOW), 0);
+    }
 }